@@ -0,0 +1,123 @@
+// import custom types, so that accounts can hold a register.
+use crate::{ account_type::AccountType, error::Error, money::Money, record::default_id, register::Register, running_balance::RunningBalance };
+
+// import Serde, so that accounts can be stored in workbooks.
+use serde::{ Serialize, Deserialize };
+
+// import Path, so that accounts can be made from bcheck files.
+use std::path::Path;
+
+/**
+ * Represents an account, such as a checking account or credit card, along with its register.
+ * # Example
+ * ```
+ * use bcheck::{ Account, AccountType, Money };
+ *
+ * let account = Account::new("Savings").with_type(AccountType::Savings).with_number("123456789").with_opening_balance(Money::from_cents(10000));
+ *
+ * assert_eq!(account.number_mask.as_deref(), Some("****6789"));
+ * assert_eq!(account.balance(), Money::from_cents(10000));
+ * ```
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Account {
+    /// account identifier.
+    #[serde(default = "default_id")]
+    pub id: String,
+
+    /// the name the account is known by, such as Checking.
+    pub name: String,
+
+    /// the bank or credit union that holds the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub institution: Option<String>,
+
+    /// the account number with all but the last four digits hidden, such as ****6789.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number_mask: Option<String>,
+
+    /// the balance the account had before the first record.
+    #[serde(default)]
+    pub opening_balance: Money,
+
+    /// the kind of account.
+    #[serde(default, rename = "type")]
+    pub account_type: AccountType,
+
+    /// the ISO 4217 code of the currency the account is kept in, such as USD.
+    #[serde(default = "default_currency")]
+    pub currency: String,
+
+    /// the records of the account.
+    #[serde(default, rename = "records")]
+    pub register: Register
+}
+
+impl Account {
+    /// create an empty checking account in US dollars with the given name.
+    pub fn new(name: &str) -> Account {
+        Account {
+            id: default_id(),
+            name: String::from(name),
+            institution: None,
+            number_mask: None,
+            opening_balance: Money::ZERO,
+            account_type: AccountType::default(),
+            currency: default_currency(),
+            register: Register::new()
+        }
+    }
+
+    /// create an account with the given name from the records in a bcheck file.
+    pub fn from_bcheck_file<P: AsRef<Path>>(name: &str, f: P) -> Result<Account, Error> {
+        Ok(Account::new(name).with_register(Register::from_file(f)?))
+    }
+
+    /// set the bank or credit union that holds the account.
+    pub fn with_institution(mut self, institution: &str) -> Account {
+        self.institution = Some(String::from(institution));
+        self
+    }
+
+    /// set the account number, keeping only the last four digits, so the full number is never stored.
+    pub fn with_number(mut self, number: &str) -> Account {
+        let digits: Vec<char> = number.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+        let last_digits: String = digits[digits.len().saturating_sub(4)..].iter().collect();
+
+        self.number_mask = Some(format!("****{}", last_digits));
+        self
+    }
+
+    /// set the balance the account had before the first record.
+    pub fn with_opening_balance(mut self, opening_balance: Money) -> Account {
+        self.opening_balance = opening_balance;
+        self
+    }
+
+    /// set the kind of account.
+    pub fn with_type(mut self, account_type: AccountType) -> Account {
+        self.account_type = account_type;
+        self
+    }
+
+    /// set the ISO 4217 code of the currency the account is kept in.
+    pub fn with_currency(mut self, currency: &str) -> Account {
+        self.currency = currency.to_uppercase();
+        self
+    }
+
+    /// set the records of the account.
+    pub fn with_register(mut self, register: Register) -> Account {
+        self.register = register;
+        self
+    }
+
+    /// the current balance, including the opening balance.
+    pub fn balance(&self) -> Money {
+        RunningBalance::new(&self.register).with_opening_balance(self.opening_balance).current()
+    }
+}
+
+fn default_currency() -> String {
+    String::from("USD")
+}
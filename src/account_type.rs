@@ -0,0 +1,54 @@
+// import Serde, so that account types can be stored with accounts.
+use serde::{ Serialize, Deserialize };
+
+// import things needed to display and parse account types.
+use std::{ fmt, str::FromStr };
+
+// import custom error, so that unknown account types can be reported.
+use crate::error::Error;
+
+/// Represents the kind of account a register is kept for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountType {
+    #[default]
+    Checking,
+    Savings,
+    CreditCard,
+    Cash,
+    Other
+}
+
+impl AccountType {
+    /// the name used for the account type in files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Checking => "checking",
+            Self::Savings => "savings",
+            Self::CreditCard => "credit_card",
+            Self::Cash => "cash",
+            Self::Other => "other"
+        }
+    }
+}
+
+impl fmt::Display for AccountType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for AccountType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace([' ', '-'], "_").as_str() {
+            "checking" => Ok(Self::Checking),
+            "savings" => Ok(Self::Savings),
+            "credit_card" | "ccard" => Ok(Self::CreditCard),
+            "cash" => Ok(Self::Cash),
+            "other" => Ok(Self::Other),
+            _ => Err(Error::Invalid { value: s.to_string(), expected: "checking, savings, credit_card, cash, or other" })
+        }
+    }
+}
@@ -0,0 +1,466 @@
+// import the library, so that records can be loaded, changed, and saved.
+use bcheck::{ Conversion, Error, Filter, Format, LocalDateTimeStringExt, Money, Record, Register, RunningBalance, Save, Transaction, TransactionType, local_midnight };
+
+// import clap, so that arguments can be read.
+use clap::{ Args, Parser, Subcommand };
+
+// import chrono, so that dates can be given.
+use chrono::{ Local, NaiveDate };
+
+// import things needed to deal with paths and exit codes.
+use std::{ io::ErrorKind, path::{ Path, PathBuf }, process::ExitCode };
+
+/// look at and change bcheck and tsv files.
+#[derive(Parser)]
+#[command(name = "bcheck", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// list records with their running balance, optionally filtered.
+    List {
+        /// the bcheck or tsv file to read.
+        file: PathBuf,
+
+        /// the balance the account had before the first record.
+        #[arg(long, default_value_t = Money::ZERO)]
+        opening_balance: Money,
+
+        #[command(flatten)]
+        filter: FilterArgs
+    },
+
+    /// add a record and print its id, creating the file if it does not exist.
+    Add {
+        /// the bcheck or tsv file to change.
+        file: PathBuf,
+
+        /// the amount of the transaction, which cannot be negative.
+        #[arg(long, value_parser = amount)]
+        amount: Money,
+
+        #[command(flatten)]
+        fields: FieldArgs
+    },
+
+    /// change the record with the given id.
+    Edit {
+        /// the bcheck or tsv file to change.
+        file: PathBuf,
+
+        /// the id of the record to change.
+        id: String,
+
+        /// the amount of the transaction, which cannot be negative.
+        #[arg(long, value_parser = amount)]
+        amount: Option<Money>,
+
+        #[command(flatten)]
+        fields: FieldArgs
+    },
+
+    /// delete the record with the given id.
+    Delete {
+        /// the bcheck or tsv file to change.
+        file: PathBuf,
+
+        /// the id of the record to delete.
+        id: String
+    },
+
+    /// show the current and reconciled balances.
+    Balance {
+        /// the bcheck or tsv file to read.
+        file: PathBuf,
+
+        /// the balance the account had before the first record.
+        #[arg(long, default_value_t = Money::ZERO)]
+        opening_balance: Money
+    },
+
+    /// mark records as reconciled.
+    Reconcile {
+        /// the bcheck or tsv file to change.
+        file: PathBuf,
+
+        /// the ids of the records to mark.
+        #[arg(required = true)]
+        ids: Vec<String>,
+
+        /// mark the records as not reconciled instead.
+        #[arg(long)]
+        undo: bool
+    },
+
+    /// convert records from one file format to another, warning about anything the new format cannot hold.
+    Convert {
+        /// the file to read, whose format is worked out from its extension or content.
+        input: PathBuf,
+
+        /// the file to write, whose format is worked out from its extension.
+        output: PathBuf,
+
+        /// the format of the input file: bcheck, tsv, csv, qif, or ofx.
+        #[arg(long)]
+        from: Option<Format>,
+
+        /// the format of the output file: bcheck, tsv, csv, qif, or ics.
+        #[arg(long)]
+        to: Option<Format>,
+
+        /// fail without writing anything if any record cannot be converted losslessly.
+        #[arg(long)]
+        strict: bool
+    }
+}
+
+/// options that pick out records to list.
+#[derive(Args)]
+struct FilterArgs {
+    /// only list records on or after this day, such as 2021-07-01.
+    #[arg(long)]
+    from: Option<NaiveDate>,
+
+    /// only list records on or before this day.
+    #[arg(long)]
+    to: Option<NaiveDate>,
+
+    /// only list records in this category.
+    #[arg(long)]
+    category: Option<String>,
+
+    /// only list records whose vendor contains this text.
+    #[arg(long)]
+    vendor: Option<String>,
+
+    /// only list deposits or withdrawals.
+    #[arg(long = "type")]
+    transaction_type: Option<TransactionType>,
+
+    /// only list reconciled records.
+    #[arg(long, conflicts_with = "unreconciled")]
+    reconciled: bool,
+
+    /// only list records that are not reconciled.
+    #[arg(long)]
+    unreconciled: bool,
+
+    /// only list records with at least this amount.
+    #[arg(long)]
+    min_amount: Option<Money>,
+
+    /// only list records with at most this amount.
+    #[arg(long)]
+    max_amount: Option<Money>
+}
+
+/// fields of a record that can be set.
+#[derive(Args)]
+struct FieldArgs {
+    /// the day of the transaction, such as 2021-07-08, which is today for new records.
+    #[arg(long)]
+    date: Option<String>,
+
+    /// the check number, or 0 to remove it.
+    #[arg(long)]
+    check_number: Option<u32>,
+
+    /// the category, or an empty string to remove it.
+    #[arg(long)]
+    category: Option<String>,
+
+    /// who the transaction was with.
+    #[arg(long)]
+    vendor: Option<String>,
+
+    /// a note about the transaction.
+    #[arg(long)]
+    memo: Option<String>,
+
+    /// deposit or withdrawal, which is withdrawal for new records.
+    #[arg(long = "type")]
+    transaction_type: Option<TransactionType>,
+
+    /// whether the transaction is reconciled.
+    #[arg(long)]
+    reconciled: Option<bool>
+}
+
+impl FilterArgs {
+    fn filter(self) -> Filter {
+        Filter {
+            from: self.from,
+            to: self.to,
+            category: self.category,
+            vendor: self.vendor,
+            transaction_type: self.transaction_type,
+            reconciled: if self.reconciled { Some(true) } else if self.unreconciled { Some(false) } else { None },
+            min_amount: self.min_amount,
+            max_amount: self.max_amount
+        }
+    }
+}
+
+impl FieldArgs {
+    // change the given transaction, keeping fields that were not given.
+    fn apply(self, transaction: &mut Transaction) -> Result<(), Error> {
+        if let Some(date) = self.date {
+            transaction.date = date.local_datetime()?;
+        }
+
+        if let Some(check_number) = self.check_number {
+            transaction.check_number = Some(check_number).filter(|check_number| *check_number > 0);
+        }
+
+        if let Some(category) = self.category {
+            transaction.category = Some(category).filter(|category| !category.is_empty());
+        }
+
+        if let Some(vendor) = self.vendor {
+            transaction.vendor = vendor;
+        }
+
+        if let Some(memo) = self.memo {
+            transaction.memo = memo;
+        }
+
+        if let Some(transaction_type) = self.transaction_type {
+            transaction.transaction_type = transaction_type;
+        }
+
+        if let Some(reconciled) = self.reconciled {
+            transaction.is_reconciled = reconciled;
+        }
+
+        Ok(())
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("bcheck: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> Result<(), Error> {
+    match command {
+        Command::List { file, opening_balance, filter } => {
+            let register = load(&file)?;
+            let filter = filter.filter();
+
+            print_records(RunningBalance::new(&register).with_opening_balance(opening_balance).iter().filter(|(record, _)| filter.matches(record)).collect());
+        },
+        Command::Add { file, amount, fields } => {
+            let mut register = match load(&file) {
+                Err(Error::Io { source, .. }) if source.kind() == ErrorKind::NotFound => Register::new(),
+                result => result?
+            };
+
+            let mut record = Record::new();
+            record.transaction.date = local_midnight(Local::now().date_naive());
+            record.transaction.amount = amount;
+            fields.apply(&mut record.transaction)?;
+            record.transaction.validate_splits()?;
+
+            let id = record.id.clone();
+
+            register.insert(record)?;
+            save(&register, &file)?;
+
+            println!("{}", id);
+        },
+        Command::Edit { file, id, amount, fields } => {
+            let mut register = load(&file)?;
+            let mut record = register.get(&id).cloned().ok_or(Error::UnknownId(id))?;
+
+            if let Some(amount) = amount {
+                record.transaction.amount = amount;
+            }
+
+            fields.apply(&mut record.transaction)?;
+            record.transaction.validate_splits()?;
+
+            register.update(record)?;
+            save(&register, &file)?;
+        },
+        Command::Delete { file, id } => {
+            let mut register = load(&file)?;
+
+            register.remove(&id).ok_or(Error::UnknownId(id))?;
+            save(&register, &file)?;
+        },
+        Command::Balance { file, opening_balance } => {
+            let register = load(&file)?;
+            let balance = RunningBalance::new(&register).with_opening_balance(opening_balance);
+
+            println!("Current balance: {}", balance.current());
+            println!("Reconciled balance: {}", balance.reconciled());
+        },
+        Command::Reconcile { file, ids, undo } => {
+            let mut register = load(&file)?;
+
+            for id in ids {
+                let mut record = register.get(&id).cloned().ok_or(Error::UnknownId(id))?;
+                record.transaction.is_reconciled = !undo;
+
+                register.update(record)?;
+            }
+
+            save(&register, &file)?;
+        },
+        Command::Convert { input, output, from, to, strict } => {
+            let to = to.or_else(|| Format::from_path(&output)).ok_or_else(|| Error::Invalid { value: output.display().to_string(), expected: "a known file extension, or a format given with --to" })?;
+
+            let conversion = Conversion::from_file(&input, from, to)?;
+            let always_lost_fields = to.always_lost_fields();
+
+            // fields that no record can keep are only mentioned once, and do not stop a strict conversion, since nothing can be done about them.
+            if !always_lost_fields.is_empty() {
+                eprintln!("bcheck: {} files do not keep {}", to, always_lost_fields.iter().map(|field| field.to_string()).collect::<Vec<String>>().join(", "));
+            }
+
+            for (id, lost_fields) in conversion.losses() {
+                eprintln!("bcheck: record {} loses {} in {}", id, lost_fields.iter().map(|field| field.to_string()).collect::<Vec<String>>().join(", "), to);
+            }
+
+            if strict && !conversion.is_lossless() {
+                return Err(Error::Invalid { value: format!("{} records that lose data", conversion.losses().len()), expected: "records that can be converted without losing anything" })
+            }
+
+            conversion.save(&output)?;
+        }
+    }
+
+    Ok(())
+}
+
+// read an amount for a record, which is never negative, since the type says which way the money went.
+fn amount(s: &str) -> Result<Money, String> {
+    match s.parse::<Money>() {
+        Ok(amount) if amount.is_negative() => Err(String::from("the amount cannot be negative, so use --type withdrawal instead")),
+        Ok(amount) => Ok(amount),
+        Err(error) => Err(error.to_string())
+    }
+}
+
+// files ending in tsv are tab delimited, and everything else is treated as a bcheck file.
+fn load(path: &Path) -> Result<Register, Error> {
+    match Format::from_path(path) {
+        Some(Format::Tsv) => Register::from_tsv_file(path),
+        _ => Register::from_file(path)
+    }
+}
+
+fn save(register: &Register, path: &Path) -> Result<(), Error> {
+    match Format::from_path(path) {
+        Some(Format::Tsv) => register.save_tsv(path),
+        _ => register.save(path)
+    }
+}
+
+// print records as a table, with withdrawals shown as negative amounts.
+fn print_records(rows: Vec<(&Record, Money)>) {
+    let vendor_width = rows.iter().map(|(record, _)| record.transaction.vendor.chars().count()).chain([6]).max().unwrap_or(6);
+    let category_width = rows.iter().map(|(record, _)| record.transaction.category.as_deref().unwrap_or("").chars().count()).chain([8]).max().unwrap_or(8);
+
+    println!("{:<36}  {:<10}  {:>6}  {:<vendor_width$}  {:<category_width$}  {:>12}  {:>12}  {:>10}", "Id", "Date", "Check", "Vendor", "Category", "Amount", "Balance", "Reconciled");
+
+    for (record, balance) in rows {
+        let transaction = &record.transaction;
+
+        println!("{:<36}  {:<10}  {:>6}  {:<vendor_width$}  {:<category_width$}  {:>12}  {:>12}  {:>10}",
+            record.id,
+            transaction.date.format("%Y-%m-%d"),
+            transaction.check_number.map(|check_number| check_number.to_string()).unwrap_or_default(),
+            transaction.vendor,
+            transaction.category.as_deref().unwrap_or(""),
+            transaction.signed_amount().to_string(),
+            balance.to_string(),
+            if transaction.is_reconciled { "Y" } else { "N" }
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // parse and run a command line, the same way main does.
+    fn run_args(args: &[&str]) -> Result<(), Error> {
+        run(Cli::try_parse_from(args).expect("arguments are valid").command)
+    }
+
+    // a path in the temp directory that does not exist yet.
+    fn temporary_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_file(&path);
+
+        path
+    }
+
+    #[test]
+    fn add_edit_reconcile_and_delete_records() {
+        let path = temporary_path("bcheck_cli_records.tsv");
+        let file = path.to_str().unwrap();
+
+        run_args(&["bcheck", "add", file, "--amount", "500", "--type", "deposit", "--vendor", "Sam Hill Credit Union", "--date", "2021-07-08"]).unwrap();
+        run_args(&["bcheck", "add", file, "--amount", "200", "--vendor", "Fake Street Electronics", "--category", "Gifts"]).unwrap();
+
+        let register = Register::from_tsv_file(&path).unwrap();
+        let id = register[0].id.clone();
+
+        assert_eq!(register.len(), 2);
+        assert_eq!(register[1].transaction.date, local_midnight(Local::now().date_naive()));
+        assert_eq!(register[1].transaction.transaction_type, TransactionType::Withdrawal);
+
+        run_args(&["bcheck", "edit", file, &id, "--amount", "450.50", "--memo", "Open Account"]).unwrap();
+        run_args(&["bcheck", "reconcile", file, &id]).unwrap();
+
+        let record = load(&path).unwrap().get(&id).cloned().unwrap();
+
+        assert_eq!(record.transaction.amount, Money::from_cents(45050));
+        assert_eq!(record.transaction.memo, "Open Account");
+        assert!(record.transaction.is_reconciled);
+
+        run_args(&["bcheck", "delete", file, &id]).unwrap();
+
+        assert!(matches!(run_args(&["bcheck", "delete", file, &id]), Err(Error::UnknownId(_))));
+        assert!(matches!(run_args(&["bcheck", "edit", file, &id, "--memo", ""]), Err(Error::UnknownId(_))));
+        assert_eq!(load(&path).unwrap().len(), 1)
+    }
+
+    #[test]
+    fn negative_amounts_are_rejected() {
+        assert!(Cli::try_parse_from(["bcheck", "add", "example.bcheck", "--amount=-5"]).is_err());
+        assert!(Cli::try_parse_from(["bcheck", "edit", "example.bcheck", "A", "--amount=-5"]).is_err());
+        assert!(Cli::try_parse_from(["bcheck", "list", "example.bcheck", "--opening-balance=-5"]).is_ok())
+    }
+
+    #[test]
+    fn strict_convert_does_not_write_lossy_files() {
+        let input = temporary_path("bcheck_cli_convert.bcheck");
+        let output = temporary_path("bcheck_cli_convert.qif");
+
+        run_args(&["bcheck", "add", input.to_str().unwrap(), "--amount", "20", "--vendor", "Fake Street Market", "--date", "2021-07-10"]).unwrap();
+        run_args(&["bcheck", "convert", input.to_str().unwrap(), output.to_str().unwrap(), "--strict"]).unwrap();
+
+        assert_eq!(Record::from_qif_file(&output).unwrap()[0].transaction.vendor, "Fake Street Market");
+
+        // spaces around a vendor are lost in QIF, so a strict conversion leaves the old file alone.
+        run_args(&["bcheck", "add", input.to_str().unwrap(), "--amount", "5", "--vendor", " Fake Street Cafe ", "--date", "2021-07-11"]).unwrap();
+
+        assert!(matches!(run_args(&["bcheck", "convert", input.to_str().unwrap(), output.to_str().unwrap(), "--strict"]), Err(Error::Invalid { .. })));
+        assert_eq!(Record::from_qif_file(&output).unwrap().len(), 1);
+
+        run_args(&["bcheck", "convert", input.to_str().unwrap(), output.to_str().unwrap()]).unwrap();
+
+        assert_eq!(Record::from_qif_file(&output).unwrap()[1].transaction.vendor, "Fake Street Cafe")
+    }
+}
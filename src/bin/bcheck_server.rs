@@ -0,0 +1,528 @@
+// import the library, so that records can be loaded, changed, and saved.
+use bcheck::{ Conversion, Error, Filter, Format, Money, Record, Register, RunningBalance, Save };
+
+// import tiny_http, so that requests can be answered.
+use tiny_http::{ Header, Method, Request, Response, Server };
+
+// import chrono, so that balances can be asked for on a given day.
+use chrono::NaiveDate;
+
+// import serde_json, so that responses can be built.
+use serde_json::json;
+
+// import things needed to share the register between threads and to write files safely.
+use std::{ env, fs::{ self, File }, io::{ BufWriter, ErrorKind }, path::PathBuf, process::ExitCode, str::FromStr, sync::{ Arc, Mutex }, thread, time::SystemTime };
+
+/// the number of requests that can be read at the same time. Changes are still made one at a time.
+const WORKERS: usize = 4;
+
+/// the register being served, along with the file it is kept in.
+struct Store {
+    path: PathBuf,
+    register: Register,
+    modified: Option<SystemTime>
+}
+
+impl Store {
+    fn open(path: PathBuf) -> Result<Store, Error> {
+        let mut store = Store {
+            path,
+            register: Register::new(),
+            modified: None
+        };
+
+        store.reload()?;
+
+        Ok(store)
+    }
+
+    // load the file again if something else changed it since it was last read or written.
+    fn reload(&mut self) -> Result<(), Error> {
+        let modified = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata.modified().ok(),
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(error) => return Err(Error::from(error))
+        };
+
+        if modified.is_none() || modified != self.modified {
+            self.register = Register::from_file(&self.path)?;
+            self.modified = modified;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * write a changed copy of the register to a file next to the real one, then move it into place,
+     * so that a reader never sees half a file and a failed write leaves the old file alone.
+     * The copy only replaces the register being served once it is written,
+     * so a failed write does not leave a change that was never saved.
+     */
+    fn save(&mut self, register: Register) -> Result<(), Error> {
+        let mut temporary_name = self.path.file_name().unwrap_or_default().to_os_string();
+        temporary_name.push(".tmp");
+        let temporary_path = self.path.with_file_name(temporary_name);
+
+        let mut output = BufWriter::new(File::create(&temporary_path)?);
+        register.to_writer(&mut output)?;
+        output.into_inner().map_err(|error| error.into_error())?.sync_all()?;
+
+        fs::rename(&temporary_path, &self.path)?;
+        self.register = register;
+        self.modified = fs::metadata(&self.path)?.modified().ok();
+
+        Ok(())
+    }
+}
+
+/// an answer to a request, before it is turned into a response.
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>
+}
+
+impl Reply {
+    fn json(status: u16, value: serde_json::Value) -> Reply {
+        Reply {
+            status,
+            content_type: "application/json",
+            body: value.to_string().into_bytes()
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Reply {
+        Reply::json(status, json!({ "error": message }))
+    }
+}
+
+// errors caused by what was sent are reported as bad requests, and everything else as a server error.
+impl From<Error> for Reply {
+    fn from(error: Error) -> Self {
+        let status = match error {
+            Error::UnknownId(_) => 404,
+            Error::DuplicateId(_) => 409,
+            Error::Io { .. } => 500,
+            _ => 400
+        };
+
+        Reply::error(status, &error.to_string())
+    }
+}
+
+fn main() -> ExitCode {
+    let mut arguments = env::args().skip(1);
+
+    let Some(path) = arguments.next().map(PathBuf::from) else {
+        eprintln!("usage: bcheck-server <file> [port]");
+        return ExitCode::FAILURE
+    };
+
+    let port = match arguments.next().map(|port| port.parse::<u16>()) {
+        None => 8080,
+        Some(Ok(port)) => port,
+        Some(Err(_)) => {
+            eprintln!("bcheck-server: the port must be a number from 0 to 65535");
+            return ExitCode::FAILURE
+        }
+    };
+
+    let store = match Store::open(path) {
+        Ok(store) => Arc::new(Mutex::new(store)),
+        Err(error) => {
+            eprintln!("bcheck-server: {}", error);
+            return ExitCode::FAILURE
+        }
+    };
+
+    // only listen on this machine, since there is no authentication.
+    let server = match Server::http(("127.0.0.1", port)) {
+        Ok(server) => Arc::new(server),
+        Err(error) => {
+            eprintln!("bcheck-server: {}", error);
+            return ExitCode::FAILURE
+        }
+    };
+
+    println!("serving {} on http://127.0.0.1:{}", store.lock().expect("store is not poisoned").path.display(), port);
+
+    let workers: Vec<thread::JoinHandle<()>> = (0..WORKERS).map(|_| {
+        let server = Arc::clone(&server);
+        let store = Arc::clone(&store);
+
+        thread::spawn(move || {
+            while let Ok(request) = server.recv() {
+                respond(request, &store);
+            }
+        })
+    }).collect();
+
+    for worker in workers {
+        let _ = worker.join();
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn respond(mut request: Request, store: &Mutex<Store>) {
+    let mut body: Vec<u8> = vec![];
+
+    let reply = match request.as_reader().read_to_end(&mut body) {
+        Ok(_) => {
+            let (segments, query) = parse_url(request.url());
+
+            // a poisoned lock only means another request panicked, and the register itself is still whole.
+            let mut store = store.lock().unwrap_or_else(|error| error.into_inner());
+
+            // a file that cannot be read is a problem on this end, whatever the error was.
+            match store.reload() {
+                Ok(()) => handle(&mut store, request.method(), &segments, &query, &body),
+                Err(error) => Reply::error(500, &error.to_string())
+            }
+        },
+        Err(error) => Reply::error(400, &error.to_string())
+    };
+
+    let content_type = Header::from_bytes(&b"Content-Type"[..], reply.content_type.as_bytes()).expect("content type is a valid header");
+    let response = Response::from_data(reply.body).with_status_code(reply.status).with_header(content_type);
+
+    if let Err(error) = request.respond(response) {
+        eprintln!("bcheck-server: {}", error);
+    }
+}
+
+// work out what the request asks for and do it.
+fn handle(store: &mut Store, method: &Method, segments: &[String], query: &[(String, String)], body: &[u8]) -> Reply {
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    let result = match (method, segments.as_slice()) {
+        (Method::Get, ["records"]) => list(store, query),
+        (Method::Post, ["records"]) => create(store, body),
+        (Method::Get, ["records", id]) => store.register.get(id).ok_or_else(|| Error::UnknownId(id.to_string())).map(|record| Reply::json(200, json!(record))),
+        (Method::Put, ["records", id]) => update(store, id, body),
+        (Method::Delete, ["records", id]) => delete(store, id),
+        (Method::Get, ["balance"]) => balance(store, query),
+        (Method::Get, ["export"]) => export(store, query),
+        (Method::Post, ["import"]) => import(store, query, body),
+        (_, ["records"] | ["records", _] | ["balance"] | ["export"] | ["import"]) => return Reply::error(405, "method not allowed"),
+        _ => return Reply::error(404, "not found")
+    };
+
+    result.unwrap_or_else(Reply::from)
+}
+
+fn list(store: &Store, query: &[(String, String)]) -> Result<Reply, Error> {
+    let filter = Filter {
+        from: parameter(query, "from")?,
+        to: parameter(query, "to")?,
+        category: parameter(query, "category")?,
+        vendor: parameter(query, "vendor")?,
+        transaction_type: parameter(query, "type")?,
+        reconciled: parameter(query, "reconciled")?,
+        min_amount: parameter(query, "min_amount")?,
+        max_amount: parameter(query, "max_amount")?
+    };
+
+    Ok(Reply::json(200, json!(filter.apply(&store.register))))
+}
+
+fn create(store: &mut Store, body: &[u8]) -> Result<Reply, Error> {
+    let record: Record = serde_json::from_slice(body)?;
+    record.transaction.validate_splits()?;
+
+    let mut register = store.register.clone();
+    register.insert(record.clone())?;
+    store.save(register)?;
+
+    Ok(Reply::json(201, json!(record)))
+}
+
+fn update(store: &mut Store, id: &str, body: &[u8]) -> Result<Reply, Error> {
+    let mut record: Record = serde_json::from_slice(body)?;
+    record.id = id.to_string();
+    record.transaction.validate_splits()?;
+
+    let mut register = store.register.clone();
+    register.update(record.clone())?;
+    store.save(register)?;
+
+    Ok(Reply::json(200, json!(record)))
+}
+
+fn delete(store: &mut Store, id: &str) -> Result<Reply, Error> {
+    let mut register = store.register.clone();
+    register.remove(id).ok_or_else(|| Error::UnknownId(id.to_string()))?;
+    store.save(register)?;
+
+    Ok(Reply { status: 204, content_type: "application/json", body: vec![] })
+}
+
+fn balance(store: &Store, query: &[(String, String)]) -> Result<Reply, Error> {
+    let balance = RunningBalance::new(&store.register).with_opening_balance(parameter(query, "opening_balance")?.unwrap_or(Money::ZERO));
+
+    let mut value = json!({
+        "current": balance.current(),
+        "reconciled": balance.reconciled()
+    });
+
+    if let Some(date) = parameter::<NaiveDate>(query, "date")? {
+        value["as_of"] = json!(balance.as_of(date));
+    }
+
+    Ok(Reply::json(200, value))
+}
+
+fn export(store: &Store, query: &[(String, String)]) -> Result<Reply, Error> {
+    let format = parameter(query, "format")?.unwrap_or(Format::Bcheck);
+    let conversion = Conversion::new(store.register.to_vec(), format);
+
+    let mut body: Vec<u8> = vec![];
+    conversion.to_writer(&mut body)?;
+
+    Ok(Reply {
+        status: 200,
+        content_type: match format {
+            Format::Bcheck => "application/json",
+            Format::Tsv => "text/tab-separated-values",
+            Format::Csv => "text/csv",
+            Format::Ics => "text/calendar",
+            Format::Qif | Format::Ofx => "application/octet-stream"
+        },
+        body
+    })
+}
+
+// add the records in the body, which can be in any format that can be read, skipping ones whose id is already in the register.
+fn import(store: &mut Store, query: &[(String, String)], body: &[u8]) -> Result<Reply, Error> {
+    let conversion = Conversion::from_reader(body, parameter(query, "format")?, Format::Bcheck)?;
+    let mut register = store.register.clone();
+    let mut imported: Vec<&str> = vec![];
+    let mut skipped: Vec<&str> = vec![];
+
+    for record in conversion.records() {
+        if register.insert(record.clone()).is_ok() {
+            imported.push(&record.id);
+        } else {
+            skipped.push(&record.id);
+        }
+    }
+
+    if !imported.is_empty() {
+        store.save(register)?;
+    }
+
+    Ok(Reply::json(200, json!({ "imported": imported, "skipped": skipped })))
+}
+
+// split a url into the decoded parts of its path and the decoded names and values in its query string.
+fn parse_url(url: &str) -> (Vec<String>, Vec<(String, String)>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<String> = path.split('/').filter(|segment| !segment.is_empty()).map(decode).collect();
+    let query: Vec<(String, String)> = query.split('&').filter(|pair| !pair.is_empty()).map(|pair| {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+        (decode(name), decode(value))
+    }).collect();
+
+    (segments, query)
+}
+
+// read a query parameter, which is absent when it is not given or empty.
+fn parameter<T>(query: &[(String, String)], name: &str) -> Result<Option<T>, Error> where T: FromStr {
+    match query.iter().find(|(parameter_name, _)| parameter_name == name).map(|(_, value)| value.as_str()).filter(|value| !value.is_empty()) {
+        Some(value) => value.parse::<T>().map(Some).map_err(|_| Error::Invalid { value: format!("{}={}", name, value), expected: "a valid query parameter" }),
+        None => Ok(None)
+    }
+}
+
+// undo percent encoding in a part of a url, treating plus signs as spaces.
+fn decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' => {
+                // a percent sign that is not followed by two hex digits is kept as it is.
+                if let Some(byte) = bytes.get(index + 1..index + 3).and_then(|hex| std::str::from_utf8(hex).ok()).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    decoded.push(byte);
+                    index += 3;
+                } else {
+                    decoded.push(b'%');
+                    index += 1;
+                }
+            },
+            b'+' => {
+                decoded.push(b' ');
+                index += 1;
+            },
+            byte => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bcheck::{ Transaction, TransactionType };
+    use serde_json::Value;
+
+    // a store kept in the temp directory, holding the given records.
+    fn temporary_store(name: &str, records: Vec<Record>) -> Store {
+        let path = std::env::temp_dir().join(name);
+        let _ = fs::remove_file(&path);
+
+        let mut store = Store::open(path).unwrap();
+        store.save(Register::try_from(records).unwrap()).unwrap();
+
+        store
+    }
+
+    // answer a request for a url, the same way respond does.
+    fn request(store: &mut Store, method: Method, url: &str, body: &[u8]) -> Reply {
+        let (segments, query) = parse_url(url);
+
+        handle(store, &method, &segments, &query, body)
+    }
+
+    fn body(reply: &Reply) -> Value {
+        serde_json::from_slice(&reply.body).unwrap()
+    }
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record::from("FF04C3DC-F0FE-472E-8737-0F4034C049F0", Transaction::from(Some("2021-7-8"), None, Some("Opening Balance"), "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, true).unwrap()),
+            Record::from("1422CBC6-7B0B-4584-B7AB-35167CC5647B", Transaction::from(Some("2021-7-8"), Some(1260), Some("Gifts"), "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("BB22187E-0BD3-41E8-B3D8-8136BD700865", Transaction::from(Some("2021-7-9"), None, Some("Groceries"), "Fake Street Market", "", 50.0, TransactionType::Withdrawal, false).unwrap())
+        ]
+    }
+
+    #[test]
+    fn create_get_update_and_delete_records() {
+        let mut store = temporary_store("bcheck_server_records.bcheck", records());
+        let record = Record::from("DE8E3D4E-B5D2-4E6B-9A1B-7F6C2B0D5E11", Transaction::from(Some("2021-7-10"), None, None, "Fake Street Cafe", "", 4.5, TransactionType::Withdrawal, false).unwrap());
+        let id = record.id.clone();
+
+        assert_eq!(request(&mut store, Method::Post, "/records", json!(record).to_string().as_bytes()).status, 201);
+        assert_eq!(request(&mut store, Method::Post, "/records", json!(record).to_string().as_bytes()).status, 409);
+        assert_eq!(request(&mut store, Method::Post, "/records", b"not a record").status, 400);
+
+        let reply = request(&mut store, Method::Get, &format!("/records/{}", id), &[]);
+
+        assert_eq!(reply.status, 200);
+        assert_eq!(body(&reply)["transaction"]["vendor"], "Fake Street Cafe");
+
+        let mut changed = record.clone();
+        changed.transaction.memo = String::from("Latte");
+
+        assert_eq!(request(&mut store, Method::Put, &format!("/records/{}", id), json!(changed).to_string().as_bytes()).status, 200);
+        assert_eq!(Register::from_file(&store.path).unwrap().get(&id).unwrap().transaction.memo, "Latte");
+
+        assert_eq!(request(&mut store, Method::Delete, &format!("/records/{}", id), &[]).status, 204);
+        assert_eq!(request(&mut store, Method::Get, &format!("/records/{}", id), &[]).status, 404);
+        assert_eq!(request(&mut store, Method::Put, &format!("/records/{}", id), json!(changed).to_string().as_bytes()).status, 404);
+        assert_eq!(request(&mut store, Method::Delete, &format!("/records/{}", id), &[]).status, 404);
+
+        assert_eq!(request(&mut store, Method::Delete, "/records", &[]).status, 405);
+        assert_eq!(request(&mut store, Method::Post, "/balance", &[]).status, 405);
+        assert_eq!(request(&mut store, Method::Get, "/accounts", &[]).status, 404);
+        assert_eq!(Register::from_file(&store.path).unwrap().len(), 3)
+    }
+
+    #[test]
+    fn failed_saves_do_not_change_the_register() {
+        let mut store = temporary_store("bcheck_server_failed_save.bcheck", records());
+        store.path = std::env::temp_dir().join("bcheck_server_missing_directory").join("register.bcheck");
+
+        assert_eq!(request(&mut store, Method::Delete, "/records/BB22187E-0BD3-41E8-B3D8-8136BD700865", &[]).status, 500);
+        assert_eq!(store.register.len(), 3)
+    }
+
+    #[test]
+    fn filter_records_with_query_parameters() {
+        let mut store = temporary_store("bcheck_server_filter.bcheck", records());
+
+        let ids = |reply: Reply| body(&reply).as_array().unwrap().iter().map(|record| record["id"].as_str().unwrap().to_string()).collect::<Vec<String>>();
+
+        assert_eq!(ids(request(&mut store, Method::Get, "/records?vendor=fake+street&type=withdrawal&from=2021-07-09", &[])), vec!["BB22187E-0BD3-41E8-B3D8-8136BD700865"]);
+        assert_eq!(ids(request(&mut store, Method::Get, "/records?category=Gifts&reconciled=false", &[])), vec!["1422CBC6-7B0B-4584-B7AB-35167CC5647B"]);
+        assert_eq!(ids(request(&mut store, Method::Get, "/records?min_amount=100&to=2021-07-08&vendor=", &[])).len(), 2);
+        assert_eq!(request(&mut store, Method::Get, "/records?from=yesterday", &[]).status, 400)
+    }
+
+    #[test]
+    fn report_balances() {
+        let mut store = temporary_store("bcheck_server_balance.bcheck", records());
+
+        let reply = request(&mut store, Method::Get, "/balance?opening_balance=10&date=2021-07-08", &[]);
+
+        assert_eq!(reply.status, 200);
+        assert_eq!(body(&reply), json!({ "current": 260.0, "reconciled": 510.0, "as_of": 310.0 }));
+        assert_eq!(body(&request(&mut store, Method::Get, "/balance", &[])), json!({ "current": 250.0, "reconciled": 500.0 }));
+        assert_eq!(request(&mut store, Method::Get, "/balance?date=2021-13-01", &[]).status, 400)
+    }
+
+    #[test]
+    fn export_with_content_types() {
+        let mut store = temporary_store("bcheck_server_export.bcheck", records());
+
+        for (query, content_type) in [("", "application/json"), ("?format=tsv", "text/tab-separated-values"), ("?format=csv", "text/csv"), ("?format=qif", "application/octet-stream")] {
+            let reply = request(&mut store, Method::Get, &format!("/export{}", query), &[]);
+
+            assert_eq!(reply.status, 200);
+            assert_eq!(reply.content_type, content_type);
+            assert!(String::from_utf8(reply.body).unwrap().contains("Fake Street Market"));
+        }
+
+        assert_eq!(request(&mut store, Method::Get, "/export?format=pdf", &[]).status, 400)
+    }
+
+    #[test]
+    fn import_skips_records_already_in_the_register() {
+        let mut source = temporary_store("bcheck_server_import_source.bcheck", records());
+        let mut store = temporary_store("bcheck_server_import.bcheck", records()[..1].to_vec());
+
+        let export = request(&mut source, Method::Get, "/export", &[]);
+        let reply = request(&mut store, Method::Post, "/import?format=bcheck", &export.body);
+
+        assert_eq!(reply.status, 200);
+        assert_eq!(body(&reply), json!({
+            "imported": ["1422CBC6-7B0B-4584-B7AB-35167CC5647B", "BB22187E-0BD3-41E8-B3D8-8136BD700865"],
+            "skipped": ["FF04C3DC-F0FE-472E-8737-0F4034C049F0"]
+        }));
+        assert_eq!(Register::from_file(&store.path).unwrap().len(), 3)
+    }
+
+    #[test]
+    fn read_query_parameters() {
+        let query = vec![(String::from("date"), String::from("2021-07-08")), (String::from("vendor"), String::new()), (String::from("amount"), String::from("ten"))];
+
+        assert_eq!(parameter::<NaiveDate>(&query, "date").unwrap(), NaiveDate::from_ymd_opt(2021, 7, 8));
+        assert_eq!(parameter::<String>(&query, "vendor").unwrap(), None);
+        assert_eq!(parameter::<String>(&query, "category").unwrap(), None);
+        assert!(matches!(parameter::<Money>(&query, "amount"), Err(Error::Invalid { .. })))
+    }
+
+    #[test]
+    fn parse_urls() {
+        assert_eq!(parse_url("/records/a%2Fb?vendor=Fake+Street&type=&reconciled"), (
+            vec![String::from("records"), String::from("a/b")],
+            vec![(String::from("vendor"), String::from("Fake Street")), (String::from("type"), String::new()), (String::from("reconciled"), String::new())]
+        ));
+        assert_eq!(parse_url("//balance/"), (vec![String::from("balance")], vec![]))
+    }
+
+    #[test]
+    fn decode_url_parts() {
+        assert_eq!(decode("a%2Fb+c"), "a/b c");
+        assert_eq!(decode("Caf%C3%A9"), "Café");
+        assert_eq!(decode("100%"), "100%");
+        assert_eq!(decode("%zz"), "%zz")
+    }
+}
@@ -0,0 +1,608 @@
+// import the library, so that records can be loaded, changed, and saved.
+use bcheck::{ Error, Format, LocalDateTimeStringExt, Money, Record, Register, RunningBalance, Save, TransactionType, local_midnight };
+
+// import ratatui, so that the register can be drawn and keys can be read.
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{ self, Event, KeyCode, KeyEventKind, KeyModifiers },
+    layout::{ Constraint, Layout },
+    style::{ Modifier, Style, Stylize },
+    text::Line,
+    widgets::{ Cell, Paragraph, Row, Table, TableState }
+};
+
+// import chrono, so that new records can be dated today.
+use chrono::Local;
+
+// import things needed to deal with paths and exit codes.
+use std::{ env, io::ErrorKind, path::{ Path, PathBuf }, process::ExitCode };
+
+/// the columns of the register that can be changed, in the order they are shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    Date,
+    CheckNumber,
+    Reconciled,
+    Category,
+    Vendor,
+    Memo,
+    Type,
+    Amount
+}
+
+impl Field {
+    const ALL: [Field; 8] = [Field::Date, Field::CheckNumber, Field::Reconciled, Field::Category, Field::Vendor, Field::Memo, Field::Type, Field::Amount];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Date => "Date",
+            Self::CheckNumber => "Check",
+            Self::Reconciled => "Rec",
+            Self::Category => "Category",
+            Self::Vendor => "Vendor",
+            Self::Memo => "Memo",
+            Self::Type => "Type",
+            Self::Amount => "Amount"
+        }
+    }
+
+    // the text to start editing from.
+    fn value(&self, record: &Record) -> String {
+        let transaction = &record.transaction;
+
+        match self {
+            Self::Date => transaction.date.format("%Y-%m-%d").to_string(),
+            Self::CheckNumber => transaction.check_number.map(|check_number| check_number.to_string()).unwrap_or_default(),
+            Self::Reconciled => String::from(if transaction.is_reconciled { "Y" } else { "" }),
+            Self::Category => transaction.category.clone().unwrap_or_default(),
+            Self::Vendor => transaction.vendor.clone(),
+            Self::Memo => transaction.memo.clone(),
+            Self::Type => String::from(if transaction.transaction_type == TransactionType::Deposit { "Deposit" } else { "Withdrawal" }),
+            Self::Amount => transaction.amount.to_string()
+        }
+    }
+
+    // set the field from what was typed, giving a reason if it could not be used.
+    fn set(&self, record: &mut Record, input: &str) -> Result<(), String> {
+        let transaction = &mut record.transaction;
+        let input = input.trim();
+
+        match self {
+            Self::Date => transaction.date = input.local_datetime().map_err(|error| error.to_string())?,
+            Self::CheckNumber => transaction.check_number = if input.is_empty() {
+                None
+            } else {
+                Some(input.parse::<u32>().map_err(|_| format!("{} is not a check number", input))?)
+            },
+            Self::Category => transaction.category = Some(input.to_string()).filter(|category| !category.is_empty()),
+            Self::Vendor => transaction.vendor = input.to_string(),
+            Self::Memo => transaction.memo = input.to_string(),
+            Self::Amount => {
+                let amount = input.parse::<Money>().map_err(|error| error.to_string())?;
+
+                // a negative amount is taken to mean a withdrawal, the same way a statement shows one.
+                if amount.is_negative() {
+                    transaction.transaction_type = TransactionType::Withdrawal;
+                }
+
+                transaction.amount = amount.abs();
+                transaction.validate_splits().map_err(|error| error.to_string())?;
+            },
+            Self::Reconciled => transaction.is_reconciled = match input {
+                "Y" | "y" => true,
+                "N" | "n" | "" => false,
+                _ => return Err(format!("{} is not Y or N", input))
+            },
+            Self::Type => transaction.transaction_type = input.parse::<TransactionType>().map_err(|error| error.to_string())?
+        }
+
+        Ok(())
+    }
+}
+
+/// what keys are being used for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Mode {
+    Browsing,
+    Editing { field: Field, input: String },
+    Searching { input: String },
+    ConfirmingDelete
+}
+
+/// the register being edited, along with what is shown on screen.
+struct App {
+    path: PathBuf,
+    register: Register,
+    is_changed: bool,
+    search: String,
+    visible: Vec<usize>,
+    table: TableState,
+    mode: Mode,
+    added_id: Option<String>,
+    message: String,
+    is_done: bool
+}
+
+impl App {
+    fn new(path: PathBuf, register: Register) -> App {
+        let mut app = App {
+            path,
+            register,
+            is_changed: false,
+            search: String::new(),
+            visible: vec![],
+            table: TableState::default().with_selected_column(Some(0)),
+            mode: Mode::Browsing,
+            added_id: None,
+            message: String::from("Arrows move, Enter edits, Space reconciles, a adds, d deletes, / searches, s saves, q saves and quits"),
+            is_done: false
+        };
+
+        app.refresh(None);
+        app.table.select(app.visible.len().checked_sub(1));
+
+        app
+    }
+
+    // work out which records match the search, keeping the given record selected if it is still shown.
+    fn refresh(&mut self, selected_id: Option<String>) {
+        let search = self.search.to_lowercase();
+
+        self.visible = self.register.iter().enumerate().filter(|(_, record)| {
+            let transaction = &record.transaction;
+
+            search.is_empty() ||
+            transaction.vendor.to_lowercase().contains(&search) ||
+            transaction.memo.to_lowercase().contains(&search) ||
+            transaction.category.as_deref().is_some_and(|category| category.to_lowercase().contains(&search)) ||
+            transaction.check_number.is_some_and(|check_number| check_number.to_string() == search)
+        }).map(|(index, _)| index).collect();
+
+        let position = selected_id.and_then(|id| self.visible.iter().position(|index| self.register[*index].id == id));
+
+        match position {
+            Some(position) => self.table.select(Some(position)),
+            None => self.table.select(self.table.selected().map(|selected| selected.min(self.visible.len().saturating_sub(1))).filter(|_| !self.visible.is_empty()))
+        }
+    }
+
+    fn selected_record(&self) -> Option<&Record> {
+        self.table.selected().and_then(|selected| self.visible.get(selected)).map(|index| &self.register[*index])
+    }
+
+    fn selected_field(&self) -> Field {
+        Field::ALL[self.table.selected_column().unwrap_or(0).min(Field::ALL.len() - 1)]
+    }
+
+    // put a changed record back in the register, following it if its date moved it.
+    fn replace(&mut self, record: Record) {
+        let id = record.id.clone();
+
+        match self.register.update(record) {
+            Ok(_) => {
+                self.is_changed = true;
+                self.refresh(Some(id));
+            },
+            Err(error) => self.message = error.to_string()
+        }
+    }
+
+    fn save(&mut self) {
+        match save(&self.register, &self.path) {
+            Ok(()) => {
+                self.is_changed = false;
+                self.message = format!("Saved {}", self.path.display());
+            },
+            Err(error) => self.message = error.to_string()
+        }
+    }
+
+    // save any changes and quit, staying open if saving failed, so that nothing is lost.
+    fn quit(&mut self) {
+        if self.is_changed {
+            self.save();
+        }
+
+        self.is_done = !self.is_changed;
+    }
+
+    // stop editing, taking out a record that was just added, since it was never filled in.
+    fn cancel_edit(&mut self) {
+        if let Some(id) = self.added_id.take() {
+            self.register.remove(&id);
+            self.refresh(None);
+        }
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        // Ctrl+C quits from anywhere, dropping whatever is being typed, but saving changes the same way q does.
+        if code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL) {
+            if matches!(self.mode, Mode::Editing { .. }) {
+                self.cancel_edit();
+            }
+
+            self.mode = Mode::Browsing;
+            self.quit();
+            return
+        }
+
+        match std::mem::replace(&mut self.mode, Mode::Browsing) {
+            Mode::Browsing => self.browse(code),
+            Mode::Editing { field, mut input } => match code {
+                KeyCode::Enter => {
+                    if let Some(mut record) = self.selected_record().cloned() {
+                        match field.set(&mut record, &input) {
+                            Ok(()) => {
+                                self.added_id = None;
+                                self.replace(record);
+                            },
+                            Err(reason) => {
+                                self.message = reason;
+                                self.mode = Mode::Editing { field, input };
+                            }
+                        }
+                    }
+                },
+                KeyCode::Esc => self.cancel_edit(),
+                KeyCode::Backspace => {
+                    input.pop();
+                    self.mode = Mode::Editing { field, input };
+                },
+                KeyCode::Char(character) => {
+                    input.push(character);
+                    self.mode = Mode::Editing { field, input };
+                },
+                _ => self.mode = Mode::Editing { field, input }
+            },
+            Mode::Searching { mut input } => match code {
+                KeyCode::Enter => {
+                    let id = self.selected_record().map(|record| record.id.clone());
+
+                    self.search = input;
+                    self.refresh(id);
+                },
+                KeyCode::Esc => {},
+                KeyCode::Backspace => {
+                    input.pop();
+                    self.mode = Mode::Searching { input };
+                },
+                KeyCode::Char(character) => {
+                    input.push(character);
+                    self.mode = Mode::Searching { input };
+                },
+                _ => self.mode = Mode::Searching { input }
+            },
+            Mode::ConfirmingDelete => if let Some(id) = self.selected_record().map(|record| record.id.clone()) && matches!(code, KeyCode::Char('y' | 'Y')) {
+                self.register.remove(&id);
+                self.is_changed = true;
+                self.message = String::from("Deleted record");
+                self.refresh(None);
+            }
+        }
+    }
+
+    fn browse(&mut self, code: KeyCode) {
+        let page = 10;
+
+        match code {
+            KeyCode::Char('q') => self.quit(),
+            KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
+            KeyCode::PageUp => self.table.scroll_up_by(page),
+            KeyCode::PageDown => self.table.scroll_down_by(page),
+            KeyCode::Home | KeyCode::Char('g') => self.table.select_first(),
+            KeyCode::End | KeyCode::Char('G') => self.table.select_last(),
+            KeyCode::Left | KeyCode::Char('h') => self.table.select_previous_column(),
+            KeyCode::Right | KeyCode::Char('l') => self.table.select_column(Some((self.table.selected_column().unwrap_or(0) + 1).min(Field::ALL.len() - 1))),
+            KeyCode::Char(' ') | KeyCode::Char('r') => self.toggle(Field::Reconciled),
+            KeyCode::Char('t') => self.toggle(Field::Type),
+            KeyCode::Enter | KeyCode::Char('e') => match self.selected_field() {
+                field @ (Field::Reconciled | Field::Type) => self.toggle(field),
+                field => if let Some(record) = self.selected_record() {
+                    self.mode = Mode::Editing { field, input: field.value(record) };
+                }
+            },
+            KeyCode::Char('a') => self.add(),
+            KeyCode::Char('d') | KeyCode::Delete if self.selected_record().is_some() => self.mode = Mode::ConfirmingDelete,
+            KeyCode::Char('/') => self.mode = Mode::Searching { input: self.search.clone() },
+            KeyCode::Esc => {
+                let id = self.selected_record().map(|record| record.id.clone());
+
+                self.search.clear();
+                self.refresh(id);
+            },
+            KeyCode::Char('s') => self.save(),
+            _ => {}
+        }
+    }
+
+    fn toggle(&mut self, field: Field) {
+        let Some(mut record) = self.selected_record().cloned() else {
+            return
+        };
+
+        match field {
+            Field::Reconciled => record.transaction.is_reconciled = !record.transaction.is_reconciled,
+            _ => record.transaction.transaction_type = match record.transaction.transaction_type {
+                TransactionType::Deposit => TransactionType::Withdrawal,
+                TransactionType::Withdrawal => TransactionType::Deposit
+            }
+        }
+
+        self.replace(record);
+    }
+
+    /**
+     * add a record for today and start editing its vendor.
+     * The register only counts as changed once the vendor is entered, and cancelling takes the record out again.
+     */
+    fn add(&mut self) {
+        let mut record = Record::new();
+        record.transaction.date = local_midnight(Local::now().date_naive());
+        let id = record.id.clone();
+
+        if let Err(error) = self.register.insert(record) {
+            self.message = error.to_string();
+            return
+        }
+
+        // clear the search, so that the new record can be seen.
+        self.added_id = Some(id.clone());
+        self.search.clear();
+        self.refresh(Some(id));
+        self.table.select_column(Field::ALL.iter().position(|field| *field == Field::Vendor));
+        self.mode = Mode::Editing { field: Field::Vendor, input: String::new() };
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [title_area, table_area, status_area] = Layout::vertical([Constraint::Length(1), Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+
+        let balance = RunningBalance::new(&self.register);
+        let balances: Vec<Money> = balance.iter().map(|(_, balance)| balance).collect();
+
+        let title = format!(" {}{}  |  Balance: {}  |  Reconciled: {}{}",
+            self.path.display(),
+            if self.is_changed { " *" } else { "" },
+            balance.current(),
+            balance.reconciled(),
+            if self.search.is_empty() { String::new() } else { format!("  |  Search: {}", self.search) }
+        );
+
+        frame.render_widget(Paragraph::new(title).add_modifier(Modifier::REVERSED), title_area);
+
+        let rows = self.visible.iter().map(|index| {
+            let record = &self.register[*index];
+            let mut cells: Vec<Cell> = Field::ALL.iter().map(|field| match field {
+                Field::Amount => Cell::from(Line::from(record.transaction.signed_amount().to_string()).right_aligned()),
+                Field::Reconciled => Cell::from(if record.transaction.is_reconciled { "Y" } else { "N" }),
+                _ => Cell::from(field.value(record))
+            }).collect();
+
+            cells.push(Cell::from(Line::from(balances[*index].to_string()).right_aligned()));
+
+            Row::new(cells)
+        });
+
+        let header = Row::new(Field::ALL.iter().map(|field| field.name()).chain(["Balance"])).add_modifier(Modifier::BOLD);
+
+        let widths = [
+            Constraint::Length(10),
+            Constraint::Length(6),
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Fill(2),
+            Constraint::Fill(2),
+            Constraint::Length(10),
+            Constraint::Length(12),
+            Constraint::Length(12)
+        ];
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .row_highlight_style(Style::new().add_modifier(Modifier::BOLD))
+            .cell_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+
+        frame.render_stateful_widget(table, table_area, &mut self.table);
+
+        let status = match &self.mode {
+            Mode::Editing { field, input } => format!("{}: {}_  (Enter to keep, Esc to cancel)  {}", field.name(), input, self.message),
+            Mode::Searching { input } => format!("Search: {}_  (Enter to search, Esc to cancel)", input),
+            Mode::ConfirmingDelete => String::from("Delete this record? (y/n)"),
+            Mode::Browsing => self.message.clone()
+        };
+
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        while !self.is_done {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? && key.kind == KeyEventKind::Press {
+                // messages about editing are only kept while the same field is being edited.
+                if self.mode == Mode::Browsing {
+                    self.message.clear();
+                }
+
+                self.handle_key(key.code, key.modifiers);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn main() -> ExitCode {
+    let Some(path) = env::args_os().nth(1).map(PathBuf::from) else {
+        eprintln!("usage: bcheck-tui <file>");
+        return ExitCode::FAILURE
+    };
+
+    // start with an empty register, so that a new file can be made.
+    let register = match load(&path) {
+        Err(Error::Io { source, .. }) if source.kind() == ErrorKind::NotFound => Register::new(),
+        Err(error) => {
+            eprintln!("bcheck-tui: {}", error);
+            return ExitCode::FAILURE
+        },
+        Ok(register) => register
+    };
+
+    let mut app = App::new(path, register);
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+
+    ratatui::restore();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("bcheck-tui: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// files ending in tsv are tab delimited, and everything else is treated as a bcheck file.
+fn load(path: &Path) -> Result<Register, Error> {
+    match Format::from_path(path) {
+        Some(Format::Tsv) => Register::from_tsv_file(path),
+        _ => Register::from_file(path)
+    }
+}
+
+fn save(register: &Register, path: &Path) -> Result<(), Error> {
+    match Format::from_path(path) {
+        Some(Format::Tsv) => register.save_tsv(path),
+        _ => register.save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bcheck::{ Split, Transaction };
+
+    fn record() -> Record {
+        Record::from("FF04C3DC-F0FE-472E-8737-0F4034C049F0", Transaction::from(Some("2021-7-8"), Some(1260), Some("Gifts"), "Fake Street Electronics", "Head set", 200.0, TransactionType::Deposit, false).unwrap())
+    }
+
+    #[test]
+    fn set_fields_from_input() {
+        let mut record = record();
+
+        Field::Date.set(&mut record, " 2021-07-09 ").unwrap();
+        Field::CheckNumber.set(&mut record, "").unwrap();
+        Field::Category.set(&mut record, "").unwrap();
+        Field::Amount.set(&mut record, "-20.50").unwrap();
+        Field::Reconciled.set(&mut record, "y").unwrap();
+
+        assert_eq!(record.transaction.date, "2021-7-9".local_datetime().unwrap());
+        assert_eq!(record.transaction.check_number, None);
+        assert_eq!(record.transaction.category, None);
+        assert_eq!(record.transaction.amount, Money::from_cents(2050));
+        assert_eq!(record.transaction.transaction_type, TransactionType::Withdrawal);
+        assert!(record.transaction.is_reconciled);
+
+        Field::Type.set(&mut record, "Deposit").unwrap();
+        Field::CheckNumber.set(&mut record, "1261").unwrap();
+
+        assert_eq!(record.transaction.transaction_type, TransactionType::Deposit);
+        assert_eq!(record.transaction.check_number, Some(1261))
+    }
+
+    #[test]
+    fn reject_input_that_cannot_be_used() {
+        let mut record = record();
+
+        assert!(Field::Date.set(&mut record, "2021-13-08").is_err());
+        assert!(Field::CheckNumber.set(&mut record, "ATM").is_err());
+        assert!(Field::Amount.set(&mut record, "12.345").is_err());
+        assert!(Field::Type.set(&mut record, "transfer").is_err());
+        assert!(Field::Reconciled.set(&mut record, "maybe").is_err());
+
+        record.transaction.set_splits(vec![Split::new(Some("Gifts"), Money::from_cents(15000)), Split::new(Some("Electronics"), Money::from_cents(5000))]).unwrap();
+
+        assert!(Field::Amount.set(&mut record, "30").is_err())
+    }
+
+    // a path in the temp directory that does not exist yet.
+    fn temporary_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_file(&path);
+
+        path
+    }
+
+    #[test]
+    fn control_c_quits_from_every_mode() {
+        let modes = [
+            Mode::Browsing,
+            Mode::Editing { field: Field::Vendor, input: String::from("Fake") },
+            Mode::Searching { input: String::from("Fake") },
+            Mode::ConfirmingDelete
+        ];
+
+        for mode in modes {
+            let mut app = App::new(temporary_path("bcheck_tui_control_c.bcheck"), Register::try_from(vec![record()]).unwrap());
+            app.mode = mode;
+
+            app.handle_key(KeyCode::Char('c'), KeyModifiers::CONTROL);
+
+            assert!(app.is_done);
+            assert_eq!(app.register.len(), 1);
+            assert!(!app.path.exists())
+        }
+    }
+
+    #[test]
+    fn control_c_saves_changes() {
+        let path = temporary_path("bcheck_tui_control_c_saves.bcheck");
+        let mut app = App::new(path.clone(), Register::try_from(vec![record()]).unwrap());
+
+        app.handle_key(KeyCode::Char('r'), KeyModifiers::NONE);
+        app.add();
+        app.handle_key(KeyCode::Char('c'), KeyModifiers::CONTROL);
+
+        let register = Register::from_file(&path).unwrap();
+
+        assert!(app.is_done);
+        assert_eq!(register.len(), 1);
+        assert!(register[0].transaction.is_reconciled)
+    }
+
+    #[test]
+    fn added_records_are_dated_today() {
+        let mut app = App::new(temporary_path("bcheck_tui_add.bcheck"), Register::new());
+
+        app.add();
+
+        assert_eq!(app.register[0].transaction.date, local_midnight(Local::now().date_naive()));
+        assert_eq!(app.mode, Mode::Editing { field: Field::Vendor, input: String::new() });
+
+        app.handle_key(KeyCode::Char('A'), KeyModifiers::SHIFT);
+        app.handle_key(KeyCode::Enter, KeyModifiers::NONE);
+
+        assert_eq!(app.register[0].transaction.vendor, "A");
+        assert!(app.is_changed)
+    }
+
+    #[test]
+    fn cancelling_an_added_record_removes_it() {
+        let mut app = App::new(temporary_path("bcheck_tui_cancel_add.bcheck"), Register::try_from(vec![record()]).unwrap());
+
+        app.add();
+        app.handle_key(KeyCode::Esc, KeyModifiers::NONE);
+
+        assert_eq!(app.register.len(), 1);
+        assert_eq!(app.mode, Mode::Browsing);
+        assert!(!app.is_changed);
+
+        // cancelling an edit of a record that was already there keeps it.
+        app.handle_key(KeyCode::Enter, KeyModifiers::NONE);
+        app.handle_key(KeyCode::Esc, KeyModifiers::NONE);
+
+        assert_eq!(app.register.len(), 1)
+    }
+}
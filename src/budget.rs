@@ -0,0 +1,104 @@
+// import custom types, so that budgets can be described and stored.
+use crate::{ companion, error::Error, money::Money, period::Period, rollover::Rollover };
+
+// import Serde, so that budgets can be stored as JSON.
+use serde::{ Serialize, Deserialize };
+
+// import chrono, so that a budget can be given a day it starts on.
+use chrono::NaiveDate;
+
+// import things needed to find budget files.
+use std::path::{ Path, PathBuf };
+
+/**
+ * Represents a limit on how much can be spent on a category in each period.
+ * Budgets are not part of the bcheck format, so they are kept in a JSON file next to the bcheck file.
+ * # Example
+ * ```
+ * use bcheck::{ Budget, Money, Period, Rollover };
+ *
+ * let budget = Budget::new("Groceries", Money::from_cents(40000)).with_rollover(Rollover::Unspent);
+ *
+ * assert_eq!(budget.period, Period::Month);
+ * ```
+ */
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Budget {
+    /// the category the budget applies to.
+    pub category: String,
+
+    /// how often the budget starts over.
+    #[serde(default = "default_period")]
+    pub period: Period,
+
+    /// how much can be spent in each period.
+    pub amount: Money,
+
+    /// what happens to what is left at the end of each period.
+    #[serde(default)]
+    pub rollover: Rollover,
+
+    /// the day the budget started, which is where rollover is counted from. When absent, the first record in the category is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<NaiveDate>
+}
+
+impl Budget {
+    /// create a monthly budget for the given category, without rollover.
+    pub fn new(category: &str, amount: Money) -> Budget {
+        Budget {
+            category: String::from(category),
+            period: default_period(),
+            amount,
+            rollover: Rollover::None,
+            start: None
+        }
+    }
+
+    /// set how often the budget starts over.
+    pub fn with_period(mut self, period: Period) -> Budget {
+        self.period = period;
+        self
+    }
+
+    /// set what happens to what is left at the end of each period.
+    pub fn with_rollover(mut self, rollover: Rollover) -> Budget {
+        self.rollover = rollover;
+        self
+    }
+
+    /// set the day the budget started, so that rollover is only counted from the period containing it.
+    pub fn with_start(mut self, start: NaiveDate) -> Budget {
+        self.start = Some(start);
+        self
+    }
+
+    /// the path of the file that holds the budgets for the given bcheck file, such as example.budgets.json for example.bcheck.
+    pub fn companion_path<P: AsRef<Path>>(bcheck_path: P) -> PathBuf {
+        companion::companion_path(bcheck_path, "budgets")
+    }
+
+    /// load budgets from the given file.
+    pub fn from_file<P: AsRef<Path>>(f: P) -> Result<Vec<Budget>, Error> {
+        companion::load(f)
+    }
+
+    /// load the budgets kept next to the given bcheck file, which gives no budgets if there is no file yet.
+    pub fn from_companion_file<P: AsRef<Path>>(bcheck_path: P) -> Result<Vec<Budget>, Error> {
+        companion::load_or_default(Budget::companion_path(bcheck_path))
+    }
+
+    /// save budgets to the given file.
+    pub fn save_file<P: AsRef<Path>>(budgets: &[Budget], f: P) -> Result<(), Error> {
+        companion::save(f, budgets)
+    }
+
+    /// save budgets next to the given bcheck file.
+    pub fn save_companion_file<P: AsRef<Path>>(budgets: &[Budget], bcheck_path: P) -> Result<(), Error> {
+        Budget::save_file(budgets, Budget::companion_path(bcheck_path))
+    }
+}
+
+fn default_period() -> Period {
+    Period::Month
+}
@@ -0,0 +1,180 @@
+// import custom types, so that budgets can be compared with what was spent.
+use crate::{ budget::Budget, csv_format::CsvFormat, error::Error, money::Money, record::Record, rollover::Rollover, transaction_type::TransactionType };
+
+// import chrono, so that periods can be worked out.
+use chrono::NaiveDate;
+
+// import Serde, so that reports can be written as JSON.
+use serde::Serialize;
+
+// import things needed to display and write reports.
+use std::{ fmt, io::Write };
+
+/// Represents how a single budget is doing in the current period.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BudgetRow {
+    /// the category of the budget.
+    pub category: String,
+
+    /// a short name for the period, such as 2021-07.
+    pub period: String,
+
+    /// the first day of the period.
+    pub start: NaiveDate,
+
+    /// the last day of the period.
+    pub end: NaiveDate,
+
+    /// the amount budgeted for the period.
+    pub budgeted: Money,
+
+    /// what was carried over from earlier periods, which is negative when overspending was carried over.
+    pub carried_over: Money,
+
+    /// the withdrawals in the category so far.
+    pub spent: Money,
+
+    /// what can still be spent, which is negative when the budget is overspent.
+    pub remaining: Money,
+
+    /// how much more than the budget has been spent, or zero.
+    pub overspent: Money,
+
+    /// what will have been spent by the end of the period, if spending keeps up the same pace.
+    pub projected: Money
+}
+
+/**
+ * Compares budgets with the withdrawals in a register, as of a given day.
+ * Rollover is worked out from the period the budget starts in, or the period of the first record in its category when no start is given.
+ * # Example
+ * ```
+ * use bcheck::{ Budget, BudgetReport, Money, Record };
+ * use chrono::NaiveDate;
+ *
+ * let records = vec![Record::new()];
+ * let budgets = vec![Budget::new("Groceries", Money::from_cents(40000))];
+ *
+ * print!("{}", BudgetReport::new(&budgets, &records, NaiveDate::from_ymd_opt(2021, 7, 15).unwrap()));
+ * ```
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BudgetReport {
+    rows: Vec<BudgetRow>
+}
+
+impl BudgetReport {
+    /// compare the given budgets with the given records, for the periods that contain the given day.
+    pub fn new(budgets: &[Budget], records: &[Record], date: NaiveDate) -> BudgetReport {
+        BudgetReport {
+            rows: budgets.iter().filter_map(|budget| row(budget, records, date)).collect()
+        }
+    }
+
+    /// the rows of the report, in the order the budgets were given.
+    pub fn rows(&self) -> &[BudgetRow] {
+        &self.rows
+    }
+
+    /// write the report as comma separated values, with a header row.
+    pub fn to_csv_writer<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        let format = CsvFormat::new();
+
+        write!(writer, "{}", format.row(&["category", "period", "start", "end", "budgeted", "carried_over", "spent", "remaining", "overspent", "projected"].map(String::from)))?;
+
+        for row in &self.rows {
+            write!(writer, "{}", format.row(&[
+                row.category.clone(),
+                row.period.clone(),
+                row.start.to_string(),
+                row.end.to_string(),
+                row.budgeted.to_string(),
+                row.carried_over.to_string(),
+                row.spent.to_string(),
+                row.remaining.to_string(),
+                row.overspent.to_string(),
+                row.projected.to_string()
+            ]))?;
+        }
+
+        Ok(())
+    }
+
+    /// write the report as JSON.
+    pub fn to_json_writer<W: Write>(&self, writer: W) -> Result<(), Error> {
+        serde_json::to_writer_pretty(writer, &self.rows)?;
+
+        Ok(())
+    }
+}
+
+// implement trait needed to display the report as a plain text table.
+impl fmt::Display for BudgetReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let category_width = self.rows.iter().map(|row| row.category.chars().count()).chain([8]).max().unwrap_or(8);
+
+        writeln!(f, "{:<category_width$}  {:>12}  {:>12}  {:>12}  {:>12}  {:>12}", "Category", "Budgeted", "Spent", "Remaining", "Overspent", "Projected")?;
+
+        for row in &self.rows {
+            writeln!(f, "{:<category_width$}  {:>12}  {:>12}  {:>12}  {:>12}  {:>12}", row.category, row.budgeted.saturating_add(row.carried_over).to_string(), row.spent.to_string(), row.remaining.to_string(), row.overspent.to_string(), row.projected.to_string())?;
+        }
+
+        Ok(())
+    }
+}
+
+// work out how a budget is doing in the period containing the given day.
+fn row(budget: &Budget, records: &[Record], date: NaiveDate) -> Option<BudgetRow> {
+    let (start, end) = budget.period.bounds(date)?;
+
+    // records in other categories say nothing about when the budget started.
+    let first_date = budget.start.or_else(|| records.iter().filter(|record| {
+        record.transaction.category_amounts().iter().any(|(category, _)| *category == Some(budget.category.as_str()))
+    }).map(|record| record.transaction.date.date_naive()).min()).unwrap_or(date).min(date);
+
+    // go through earlier periods to find what was carried over.
+    let mut carried_over = Money::ZERO;
+
+    if budget.rollover != Rollover::None {
+        let mut period = budget.period.bounds(first_date);
+
+        while let Some((period_start, period_end)) = period.filter(|(period_start, _)| *period_start < start) {
+            let left_over = budget.amount.saturating_add(carried_over).saturating_sub(spent(records, &budget.category, period_start, period_end));
+
+            carried_over = match budget.rollover {
+                Rollover::Unspent => left_over.max(Money::ZERO),
+                _ => left_over
+            };
+
+            period = budget.period.next_start(period_start).and_then(|next_start| budget.period.bounds(next_start));
+        }
+    }
+
+    let spent = spent(records, &budget.category, start, date.min(end));
+    let remaining = budget.amount.saturating_add(carried_over).saturating_sub(spent);
+
+    // assume spending carries on at the same pace for the rest of the period.
+    let elapsed_days = ((date.min(end) - start).num_days() + 1) as i128;
+    let total_days = ((end - start).num_days() + 1) as i128;
+    let projected = i64::try_from((spent.cents() as i128 * total_days + elapsed_days / 2) / elapsed_days).map(Money::from_cents).unwrap_or(spent);
+
+    Some(BudgetRow {
+        category: budget.category.clone(),
+        period: budget.period.label(start),
+        start,
+        end,
+        budgeted: budget.amount,
+        carried_over,
+        spent,
+        remaining,
+        overspent: Money::ZERO.saturating_sub(remaining).max(Money::ZERO),
+        projected
+    })
+}
+
+// add up the withdrawals in the given category from start to end, including both days, counting only the matching splits of split transactions.
+fn spent(records: &[Record], category: &str, start: NaiveDate, end: NaiveDate) -> Money {
+    records.iter().filter(|record| {
+        record.transaction.transaction_type == TransactionType::Withdrawal && (start..=end).contains(&record.transaction.date.date_naive())
+    }).flat_map(|record| record.transaction.category_amounts()).filter(|(split_category, _)| *split_category == Some(category)).fold(Money::ZERO, |total, (_, amount)| total.saturating_add(amount))
+}
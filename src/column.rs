@@ -0,0 +1,71 @@
+use crate::error::Error;
+use std::{ fmt, str::FromStr };
+
+/// Represent a column that can appear in delimited data, such as TSV.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Column {
+    Id,
+    Date,
+    CheckNumber,
+    Reconciled,
+    Category,
+    Vendor,
+    Memo,
+    Deposit,
+    Withdrawal,
+
+    /// a signed amount, where deposits are positive and withdrawals are negative.
+    Amount,
+
+    /// a column that is not used, such as a running balance added in a spreadsheet.
+    Ignored
+}
+
+impl Column {
+    /// the name used for the column in a header row.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Date => "date",
+            Self::CheckNumber => "check_number",
+            Self::Reconciled => "reconciled",
+            Self::Category => "category",
+            Self::Vendor => "vendor",
+            Self::Memo => "memo",
+            Self::Deposit => "deposit",
+            Self::Withdrawal => "withdrawal",
+            Self::Amount => "amount",
+            Self::Ignored => ""
+        }
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// parse column names leniently, since spreadsheet headers are written by hand.
+impl FromStr for Column {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s.trim().to_lowercase().chars().filter(|c| c.is_alphanumeric() || *c == '#').collect();
+
+        match name.as_str() {
+            "id" | "uuid" => Ok(Self::Id),
+            "date" => Ok(Self::Date),
+            "checknumber" | "check" | "check#" | "num" | "number" => Ok(Self::CheckNumber),
+            "reconciled" | "cleared" => Ok(Self::Reconciled),
+            "category" => Ok(Self::Category),
+            "vendor" | "payee" => Ok(Self::Vendor),
+            "memo" | "notes" => Ok(Self::Memo),
+            "deposit" | "credit" => Ok(Self::Deposit),
+            "withdrawal" | "debit" => Ok(Self::Withdrawal),
+            "amount" => Ok(Self::Amount),
+            "balance" | "runningbalance" => Ok(Self::Ignored),
+            _ => Err(Error::Invalid { value: s.to_string(), expected: "a column name" })
+        }
+    }
+}
@@ -0,0 +1,36 @@
+// import custom error and file writing, so that companion files behave like bcheck files.
+use crate::{ error::Error, save_vec::write_file };
+
+// import serde, so that companion files can hold anything that can be stored as JSON.
+use serde::{ Serialize, de::DeserializeOwned };
+
+// import things needed for dealing with files.
+use std::{ fs::File, io::{ BufReader, ErrorKind }, path::{ Path, PathBuf } };
+
+/**
+ * the path of a file kept next to a bcheck file, for data BCheckbook does not know about.
+ * For example, the budgets for example.bcheck are kept in example.budgets.json.
+ */
+pub(crate) fn companion_path<P: AsRef<Path>>(path: P, name: &str) -> PathBuf {
+    path.as_ref().with_extension(format!("{}.json", name))
+}
+
+/// load JSON from the given path.
+pub(crate) fn load<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T, Error> {
+    let file = File::open(&path).map_err(|error| Error::from(error).with_path(&path))?;
+
+    serde_json::from_reader(BufReader::new(file)).map_err(|error| Error::from(error).with_path(path))
+}
+
+/// load JSON from the given path, giving the default value if the file does not exist yet.
+pub(crate) fn load_or_default<T: DeserializeOwned + Default, P: AsRef<Path>>(path: P) -> Result<T, Error> {
+    match load(&path) {
+        Err(Error::Io { source, .. }) if source.kind() == ErrorKind::NotFound => Ok(T::default()),
+        result => result
+    }
+}
+
+/// save JSON to the given path.
+pub(crate) fn save<T: Serialize + ?Sized, P: AsRef<Path>>(path: P, value: &T) -> Result<(), Error> {
+    write_file(&path, |output| Ok(serde_json::to_writer_pretty(output, value)?))
+}
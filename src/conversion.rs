@@ -0,0 +1,110 @@
+// import custom types, so that records can be moved between formats.
+use crate::{ error::Error, format::Format, lost_field::LostField, record::Record, save_vec::write_file };
+
+// import things needed for dealing with files.
+use std::{ fs, io::{ Read, Write }, path::Path };
+
+/**
+ * Converts records from one file format to another, keeping track of records that the target format cannot hold as they are.
+ * # Example
+ * ```
+ * use bcheck::{ Conversion, Format };
+ *
+ * if let Ok(conversion) = Conversion::from_file("/Users/bob/Documents/example.bcheck", None, Format::Qif) {
+ *     for (id, lost_fields) in conversion.losses() {
+ *         println!("{} loses {:?}", id, lost_fields)
+ *     }
+ *
+ *     conversion.save("/Users/bob/Documents/example.qif").unwrap();
+ * }
+ * ```
+ */
+#[derive(Clone, Debug)]
+pub struct Conversion {
+    from: Option<Format>,
+    to: Format,
+    records: Vec<Record>,
+    losses: Vec<(String, Vec<LostField>)>
+}
+
+impl Conversion {
+    /// prepare records that are already loaded to be written in the given format.
+    pub fn new(records: Vec<Record>, to: Format) -> Conversion {
+        let losses = records.iter().map(|record| (record.id.clone(), to.lost_fields(record))).filter(|(_, lost_fields)| !lost_fields.is_empty()).collect();
+
+        Conversion {
+            from: None,
+            to,
+            records,
+            losses
+        }
+    }
+
+    /// read records from the given path, working out its format from the extension or content if it is not given.
+    pub fn from_file<P: AsRef<Path>>(f: P, from: Option<Format>, to: Format) -> Result<Conversion, Error> {
+        let content = fs::read(&f).map_err(|error| Error::from(error).with_path(&f))?;
+        let from = from.or_else(|| Format::detect(&f, &content)).ok_or_else(|| Error::Invalid { value: f.as_ref().display().to_string(), expected: "a file in a known format" })?;
+
+        Conversion::from_content(&content, from, to).map_err(|error| error.with_path(f))
+    }
+
+    /// read records from anything that can be read, working out the format from the content if it is not given.
+    pub fn from_reader<R: Read>(mut reader: R, from: Option<Format>, to: Format) -> Result<Conversion, Error> {
+        let mut content: Vec<u8> = vec![];
+        reader.read_to_end(&mut content)?;
+
+        let from = match from.or_else(|| Format::sniff(&content)) {
+            Some(from) => from,
+            None => return Err(Error::Invalid { value: String::new(), expected: "data in a known format" })
+        };
+
+        Conversion::from_content(&content, from, to)
+    }
+
+    // read records in the given format, failing early if the target format cannot be written.
+    fn from_content(content: &[u8], from: Format, to: Format) -> Result<Conversion, Error> {
+        if !to.can_write() {
+            return Err(Error::Invalid { value: to.to_string(), expected: "a format that can be written" })
+        }
+
+        let mut conversion = Conversion::new(from.read(content)?, to);
+        conversion.from = Some(from);
+
+        Ok(conversion)
+    }
+
+    /// the format the records were read from, if they were read by the conversion.
+    pub fn from_format(&self) -> Option<Format> {
+        self.from
+    }
+
+    /// the format the records will be written in.
+    pub fn to_format(&self) -> Format {
+        self.to
+    }
+
+    /// the records being converted.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// the id of each record that cannot be written losslessly, along with what would be lost, in the order the records were read.
+    pub fn losses(&self) -> &[(String, Vec<LostField>)] {
+        &self.losses
+    }
+
+    /// whether every record can be written without losing anything.
+    pub fn is_lossless(&self) -> bool {
+        self.losses.is_empty()
+    }
+
+    /// write the records in the target format to anything that can be written to.
+    pub fn to_writer<W: Write>(&self, writer: W) -> Result<(), Error> {
+        self.to.write(&self.records, writer)
+    }
+
+    /// write the records in the target format to the given path.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        write_file(&path, |output| self.to_writer(output))
+    }
+}
@@ -0,0 +1,175 @@
+// import custom types, so that rows can be turned into records and back.
+use crate::{ error::Error, layout::Layout, record::Record, save_vec::write_file };
+
+// import things needed to write records out.
+use std::{ io::Write, path::Path };
+
+/**
+ * Describes how comma separated values are read and written.
+ * Fields are quoted as described in RFC 4180, so values can contain the delimiter, quotes, and line breaks.
+ * By default, commas separate fields and a header row is written, with the same columns as TSV files.
+ * # Example
+ * ```
+ * use bcheck::{ CsvFormat, Layout };
+ *
+ * let format = CsvFormat::new().with_delimiter(';').with_layout(Layout::default().with_signed_amount().with_header(true));
+ * ```
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsvFormat {
+    delimiter: char,
+    layout: Layout
+}
+
+impl CsvFormat {
+    /// create a format that uses commas and the default layout with a header.
+    pub fn new() -> CsvFormat {
+        CsvFormat {
+            delimiter: ',',
+            layout: Layout::default().with_header(true)
+        }
+    }
+
+    /// set the character that separates fields, such as ';' or '\t'.
+    pub fn with_delimiter(mut self, delimiter: char) -> CsvFormat {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// set the columns to read and write.
+    pub fn with_layout(mut self, layout: Layout) -> CsvFormat {
+        self.layout = layout;
+        self
+    }
+
+    /// the character that separates fields.
+    pub fn delimiter(&self) -> char {
+        self.delimiter
+    }
+
+    /// the columns that are read and written.
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// write records as comma separated values to anything that can be written to.
+    pub fn write<W: Write>(&self, records: &[Record], mut writer: W) -> Result<(), Error> {
+        write!(writer, "{}", self.string_from_records(records))?;
+
+        Ok(())
+    }
+
+    /// save records as comma separated values to the given path.
+    pub fn save<P: AsRef<Path>>(&self, records: &[Record], path: P) -> Result<(), Error> {
+        write_file(&path, |output| self.write(records, output))
+    }
+
+    /// turn CSV text into records, collecting the rows that could not be read.
+    pub(crate) fn records_from_str(&self, text: &str) -> Result<(Vec<Record>, Vec<Error>), Error> {
+        self.layout.records_from_rows(self.rows(text)?.into_iter().map(Ok))
+    }
+
+    /// turn records into CSV text.
+    pub(crate) fn string_from_records(&self, records: &[Record]) -> String {
+        let mut csv_string = String::new();
+
+        if self.layout.has_header() {
+            csv_string.push_str(&self.row(&self.layout.header_fields()));
+        }
+
+        for record in records {
+            csv_string.push_str(&self.row(&self.layout.fields_from_record(record)));
+        }
+
+        csv_string
+    }
+
+    /// join fields into a single row, quoting the ones that need it.
+    pub(crate) fn row(&self, fields: &[String]) -> String {
+        let quoted_fields: Vec<String> = fields.iter().map(|field| {
+            if field.contains([self.delimiter, '"', '\r', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        }).collect();
+
+        format!("{}\r\n", quoted_fields.join(&self.delimiter.to_string()))
+    }
+
+    // split text into rows of fields, along with the line each row starts on.
+    fn rows(&self, text: &str) -> Result<Vec<(usize, Vec<String>)>, Error> {
+        let mut rows: Vec<(usize, Vec<String>)> = vec![];
+        let mut fields: Vec<String> = vec![];
+        let mut field = String::new();
+        let mut line = 1;
+        let mut row_line = 1;
+        let mut is_quoted = false;
+        let mut has_content = false;
+
+        // spreadsheets like to begin files with a byte order mark, which is not part of the data.
+        let mut characters = text.strip_prefix('\u{feff}').unwrap_or(text).chars().peekable();
+
+        while let Some(character) = characters.next() {
+            if is_quoted {
+                match character {
+                    '"' if characters.peek() == Some(&'"') => {
+                        characters.next();
+                        field.push('"');
+                    },
+                    '"' => is_quoted = false,
+                    '\n' => {
+                        line += 1;
+                        field.push(character);
+                    },
+                    _ => field.push(character)
+                }
+
+                continue
+            }
+
+            match character {
+                '"' if field.is_empty() => {
+                    is_quoted = true;
+                    has_content = true;
+                },
+                '\r' | '\n' => {
+                    if character == '\r' && characters.peek() == Some(&'\n') {
+                        characters.next();
+                    }
+
+                    if has_content || !fields.is_empty() {
+                        fields.push(std::mem::take(&mut field));
+                        rows.push((row_line, std::mem::take(&mut fields)));
+                    }
+
+                    has_content = false;
+                    line += 1;
+                    row_line = line;
+                },
+                _ if character == self.delimiter => fields.push(std::mem::take(&mut field)),
+                _ => {
+                    field.push(character);
+                    has_content = true;
+                }
+            }
+        }
+
+        if is_quoted {
+            return Err(Error::Field { line: Some(row_line), column: fields.len() + 1, value: field, source: Some(Box::new(Error::Invalid { value: String::new(), expected: "a closing quote" })) })
+        }
+
+        if has_content || !fields.is_empty() {
+            fields.push(field);
+            rows.push((row_line, fields));
+        }
+
+        Ok(rows)
+    }
+}
+
+impl Default for CsvFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
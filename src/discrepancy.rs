@@ -0,0 +1,217 @@
+// import custom types, so that records can be checked against a difference.
+use crate::{ money::Money, record::Record };
+
+// import BTreeMap, so that records sharing a check number can be grouped in order.
+use std::collections::BTreeMap;
+
+/// Represents a possible reason a balance does not match a bank statement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Discrepancy {
+    /// records that were left out of the balance, but add up to the difference, so they may have cleared without being marked.
+    Uncleared(Vec<String>),
+
+    /// a record whose amount may have been entered with two digits swapped. amount is what the statement probably shows.
+    TransposedDigits { id: String, amount: Money },
+
+    /// a record that may have been entered as a deposit when it was a withdrawal, or the other way around.
+    SignFlip { id: String },
+
+    /// records that share a check number, which may mean a check was entered twice.
+    DuplicateCheckNumber { check_number: u32, ids: Vec<String> },
+
+    /// the search for uncleared records stopped after trying the given number of groups, so other groups may also add up to the difference.
+    SearchLimitReached { groups: usize }
+}
+
+/**
+ * Looks for reasons a balance does not match a bank statement.
+ * The difference given to find() is what the statement shows minus what the register shows,
+ * and records that are not counted in the balance are only looked at as a group that may have cleared.
+ * # Example
+ * ```
+ * use bcheck::{ DiscrepancyFinder, Money, Record };
+ *
+ * let records = vec![Record::new()];
+ *
+ * for discrepancy in DiscrepancyFinder::new(&records).find(Money::from_cents(-900)) {
+ *     println!("{:?}", discrepancy)
+ * }
+ * ```
+ */
+#[derive(Clone, Debug)]
+pub struct DiscrepancyFinder<'a> {
+    counted: Vec<&'a Record>,
+    uncounted: Vec<&'a Record>,
+    max_subset_size: usize,
+    max_results: usize,
+    max_groups: usize
+}
+
+impl<'a> DiscrepancyFinder<'a> {
+    /// look through the given records, where the reconciled records are the ones counted in the balance.
+    pub fn new(records: &'a [Record]) -> DiscrepancyFinder<'a> {
+        let (counted, uncounted) = records.iter().partition(|record| record.transaction.is_reconciled);
+
+        DiscrepancyFinder::from_parts(counted, uncounted)
+    }
+
+    /// look through records that are already split into the ones counted in the balance and the ones left out.
+    pub(crate) fn from_parts(counted: Vec<&'a Record>, uncounted: Vec<&'a Record>) -> DiscrepancyFinder<'a> {
+        DiscrepancyFinder {
+            counted,
+            uncounted,
+            max_subset_size: 3,
+            max_results: 20,
+            max_groups: 1_000_000
+        }
+    }
+
+    /**
+     * set the largest number of records that can be grouped together to make up the difference. The default is 3.
+     * The search takes much longer as this grows, so keep it small for large registers.
+     */
+    pub fn with_max_subset_size(mut self, max_subset_size: usize) -> DiscrepancyFinder<'a> {
+        self.max_subset_size = max_subset_size;
+        self
+    }
+
+    /**
+     * set the most groups of records that are tried before the search for uncleared records stops, which keeps it quick
+     * when many records are left out of the balance. The default is 1,000,000, and a SearchLimitReached discrepancy is given when it is hit.
+     */
+    pub fn with_max_groups(mut self, max_groups: usize) -> DiscrepancyFinder<'a> {
+        self.max_groups = max_groups;
+        self
+    }
+
+    /// set the most groups of records that are given, since small amounts can often be made many ways. The default is 20.
+    pub fn with_max_results(mut self, max_results: usize) -> DiscrepancyFinder<'a> {
+        self.max_results = max_results;
+        self
+    }
+
+    /// propose explanations for the given difference, with the simplest ones first.
+    pub fn find(&self, difference: Money) -> Vec<Discrepancy> {
+        let mut discrepancies: Vec<Discrepancy> = vec![];
+
+        if !difference.is_zero() {
+            discrepancies.extend(self.uncleared_subsets(difference));
+
+            for record in &self.counted {
+                let signed_amount = record.transaction.signed_amount();
+
+                // the statement shows the opposite of what was entered, so the balance is off by twice the amount.
+                if signed_amount.checked_mul(-2) == Some(difference) {
+                    discrepancies.push(Discrepancy::SignFlip { id: record.id.clone() });
+                }
+            }
+
+            // swapping two digits always changes a number by a multiple of 9.
+            if difference.cents() % 9 == 0 {
+                for record in &self.counted {
+                    let signed_amount = record.transaction.signed_amount();
+
+                    for amount in transpositions(record.transaction.amount) {
+                        let statement_amount = if signed_amount.is_negative() { -amount } else { amount };
+
+                        if statement_amount.checked_sub(signed_amount) == Some(difference) {
+                            discrepancies.push(Discrepancy::TransposedDigits { id: record.id.clone(), amount });
+                        }
+                    }
+                }
+            }
+        }
+
+        discrepancies.extend(self.duplicate_check_numbers());
+
+        discrepancies
+    }
+
+    // find groups of uncounted records that add up to the difference, smallest groups first.
+    fn uncleared_subsets(&self, difference: Money) -> Vec<Discrepancy> {
+        let mut subsets: Vec<Vec<usize>> = vec![];
+        let mut groups = 0;
+
+        for size in 1..=self.max_subset_size.min(self.uncounted.len()) {
+            let mut indices: Vec<usize> = vec![];
+
+            self.search(0, size, Money::ZERO, difference, &mut indices, &mut subsets, &mut groups);
+        }
+
+        let mut discrepancies: Vec<Discrepancy> = subsets.into_iter().map(|indices| Discrepancy::Uncleared(indices.into_iter().map(|index| self.uncounted[index].id.clone()).collect())).collect();
+
+        if groups >= self.max_groups && discrepancies.len() < self.max_results {
+            discrepancies.push(Discrepancy::SearchLimitReached { groups });
+        }
+
+        discrepancies
+    }
+
+    /**
+     * go through every combination of the given size, starting at the given record.
+     * groups counts every group tried, including partial ones, so that the search stops at the limit however the records are made up.
+     */
+    #[allow(clippy::too_many_arguments)]
+    fn search(&self, start: usize, size: usize, total: Money, difference: Money, indices: &mut Vec<usize>, subsets: &mut Vec<Vec<usize>>, groups: &mut usize) {
+        if subsets.len() >= self.max_results || *groups >= self.max_groups {
+            return
+        }
+
+        if indices.len() == size {
+            if total == difference {
+                subsets.push(indices.clone());
+            }
+
+            return
+        }
+
+        for index in start..self.uncounted.len() {
+            let Some(total) = total.checked_add(self.uncounted[index].transaction.signed_amount()) else {
+                continue
+            };
+
+            if *groups >= self.max_groups {
+                return
+            }
+
+            *groups += 1;
+            indices.push(index);
+            self.search(index + 1, size, total, difference, indices, subsets, groups);
+            indices.pop();
+        }
+    }
+
+    // group every record by check number, keeping the numbers used more than once.
+    fn duplicate_check_numbers(&self) -> Vec<Discrepancy> {
+        let mut check_numbers: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+
+        for record in self.counted.iter().chain(self.uncounted.iter()) {
+            if let Some(check_number) = record.transaction.check_number {
+                check_numbers.entry(check_number).or_default().push(record.id.clone());
+            }
+        }
+
+        check_numbers.into_iter().filter(|(_, ids)| ids.len() > 1).map(|(check_number, ids)| Discrepancy::DuplicateCheckNumber { check_number, ids }).collect()
+    }
+}
+
+// every amount that can be made by swapping two neighboring digits of the given amount.
+fn transpositions(amount: Money) -> Vec<Money> {
+    let digits: Vec<char> = amount.abs().cents().to_string().chars().collect();
+    let mut amounts: Vec<Money> = vec![];
+
+    for index in 1..digits.len() {
+        if digits[index - 1] == digits[index] {
+            continue
+        }
+
+        let mut swapped_digits = digits.clone();
+        swapped_digits.swap(index - 1, index);
+
+        if let Ok(cents) = swapped_digits.into_iter().collect::<String>().parse::<i64>() {
+            amounts.push(Money::from_cents(cents));
+        }
+    }
+
+    amounts
+}
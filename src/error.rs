@@ -0,0 +1,181 @@
+// import custom error, so that it can be wrapped.
+use crate::{ money::Money, money_parse_error::MoneyParseError, transaction_type_parse_error::TransactionTypeParseError };
+
+// import things needed to describe errors and where they came from.
+use std::{ error, fmt, io, path::{ Path, PathBuf } };
+
+/// Represents everything that can go wrong while loading, saving, or parsing bcheck data.
+#[derive(Debug)]
+pub enum Error {
+    /// reading or writing failed. The path is included when a file was involved.
+    Io { path: Option<PathBuf>, source: io::Error },
+
+    /// JSON could not be read or written. The path is included when a file was involved.
+    Json { path: Option<PathBuf>, source: serde_json::Error },
+
+    /**
+     * a field in a tab delimited row could not be used.
+     * line and column both start at 1, and line is absent when a row is parsed on its own.
+     * source holds the reason the value was rejected, and is absent when the column is missing altogether.
+     */
+    Field { line: Option<usize>, column: usize, value: String, source: Option<Box<Error>> },
+
+    /**
+     * an entry in a statement, such as an OFX element or a QIF field, could not be used.
+     * name identifies the entry, and source holds the reason the value was rejected, which is absent when the entry is missing.
+     */
+    Entry { line: usize, name: String, value: String, source: Option<Box<Error>> },
+
+    /// a date string was not in the format of YYYY-MM-DD, or does not name a real day.
+    Date(String),
+
+    /// an amount could not be read as an exact amount of money.
+    Amount { value: String, source: MoneyParseError },
+
+    /// a transaction type was neither a deposit or a withdrawal.
+    Type(TransactionTypeParseError),
+
+    /// a value was not what was expected, such as a check number that is not a number.
+    Invalid { value: String, expected: &'static str },
+
+    /// several rows could not be read. Each error describes one row.
+    Rows(Vec<Error>),
+
+    /// a record could not be added, because another record already has its id.
+    DuplicateId(String),
+
+    /// no record has the given id.
+    UnknownId(String),
+
+    /// a reconciliation could not be completed, because the cleared balance is off from the statement by the given amount.
+    Unbalanced(Money),
+
+    /// the record with the given id was changed after a reconciliation started.
+    Changed(String),
+
+    /// an account could not be added, because another account already has the given id or name.
+    DuplicateAccount(String),
+
+    /// no account has the given id.
+    UnknownAccount(String),
+
+    /// the splits of a transaction do not add up to its amount. total is absent if the splits are too large to add up.
+    Splits { amount: Money, total: Option<Money> },
+
+    /// the SQLite database could not be read or written.
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error)
+}
+
+impl Error {
+    /// attach a file path to I/O and JSON errors, so the caller knows which file was the problem.
+    pub(crate) fn with_path<P: AsRef<Path>>(self, path: P) -> Error {
+        match self {
+            Self::Io { source, .. } => Self::Io { path: Some(path.as_ref().to_path_buf()), source },
+            Self::Json { source, .. } => Self::Json { path: Some(path.as_ref().to_path_buf()), source },
+            error => error
+        }
+    }
+
+    /// attach a line number to field errors, so the caller knows which row was the problem.
+    pub(crate) fn at_line(self, line: usize) -> Error {
+        match self {
+            Self::Field { column, value, source, .. } => Self::Field { line: Some(line), column, value, source },
+            error => error
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io { path: Some(path), source } => write!(f, "{}: {}", path.display(), source),
+            Self::Io { path: None, source } => write!(f, "{}", source),
+            Self::Json { path: Some(path), source } => write!(f, "{}: invalid JSON: {}", path.display(), source),
+            Self::Json { path: None, source } => write!(f, "invalid JSON: {}", source),
+            Self::Field { line, column, value, source } => {
+                if let Some(line) = line {
+                    write!(f, "line {}, ", line)?;
+                }
+
+                match source {
+                    Some(source) => write!(f, "column {}: \"{}\" could not be used: {}", column, value, source),
+                    None => write!(f, "column {} is missing", column)
+                }
+            },
+            Self::Entry { line, name, value, source } => match source {
+                Some(source) => write!(f, "line {}, {}: \"{}\" could not be used: {}", line, name, value, source),
+                None => write!(f, "line {}: {} is missing", line, name)
+            },
+            Self::Date(s) => write!(f, "{} is not a valid date. String must be in the format of YYYY-MM-DD.", s),
+            Self::Amount { value, source } => write!(f, "{} is not a valid amount: {}", value, source),
+            Self::Type(error) => write!(f, "{}", error),
+            Self::Invalid { value, expected } => write!(f, "expected {}, but found \"{}\"", expected, value),
+            Self::Rows(errors) => {
+                write!(f, "{} rows could not be read", errors.len())?;
+
+                for error in errors {
+                    write!(f, "\n{}", error)?;
+                }
+
+                Ok(())
+            },
+            Self::DuplicateId(id) => write!(f, "a record with the id {} already exists", id),
+            Self::UnknownId(id) => write!(f, "no record has the id {}", id),
+            Self::Unbalanced(difference) => write!(f, "the cleared balance is off from the statement by {}", difference),
+            Self::Changed(id) => write!(f, "the record with the id {} was changed during reconciliation", id),
+            Self::DuplicateAccount(account) => write!(f, "an account called {} already exists", account),
+            Self::UnknownAccount(id) => write!(f, "no account has the id {}", id),
+            Self::Splits { amount, total: Some(total) } => write!(f, "splits add up to {}, but the amount is {}", total, amount),
+            Self::Splits { amount, total: None } => write!(f, "splits are too large to add up to {}", amount),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(error) => write!(f, "database error: {}", error)
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Json { source, .. } => Some(source),
+            Self::Field { source: Some(source), .. } => Some(source.as_ref()),
+            Self::Entry { source: Some(source), .. } => Some(source.as_ref()),
+            Self::Amount { source, .. } => Some(source),
+            Self::Type(error) => Some(error),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io { path: None, source: error }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        // failures of the underlying reader or writer are not problems with the JSON itself.
+        if error.is_io() {
+            Self::Io { path: None, source: error.into() }
+        } else {
+            Self::Json { path: None, source: error }
+        }
+    }
+}
+
+impl From<TransactionTypeParseError> for Error {
+    fn from(error: TransactionTypeParseError) -> Self {
+        Self::Type(error)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for Error {
+    fn from(error: rusqlite::Error) -> Self {
+        Self::Sqlite(error)
+    }
+}
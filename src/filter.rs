@@ -0,0 +1,118 @@
+// import custom types, so that records can be picked out.
+use crate::{ money::Money, record::Record, transaction_type::TransactionType };
+
+// import chrono, so that records can be filtered by date.
+use chrono::NaiveDate;
+
+/**
+ * Picks out records by date, category, vendor, type, amount, and whether they are reconciled.
+ * Every condition that is set has to match, and an empty filter matches every record.
+ * # Example
+ * ```
+ * use bcheck::{ Filter, Record };
+ * use chrono::NaiveDate;
+ *
+ * let records = vec![Record::new()];
+ * let filter = Filter::new().with_from(NaiveDate::from_ymd_opt(2021, 7, 1).unwrap()).with_reconciled(false);
+ *
+ * for record in filter.apply(&records) {
+ *     println!("{}", record.transaction.vendor)
+ * }
+ * ```
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Filter {
+    /// the first day to include.
+    pub from: Option<NaiveDate>,
+
+    /// the last day to include.
+    pub to: Option<NaiveDate>,
+
+    /// the category to include, ignoring case, which also matches the categories of splits.
+    pub category: Option<String>,
+
+    /// text the vendor has to contain, ignoring case.
+    pub vendor: Option<String>,
+
+    /// the type of transaction to include.
+    pub transaction_type: Option<TransactionType>,
+
+    /// whether to include only reconciled or only unreconciled records.
+    pub reconciled: Option<bool>,
+
+    /// the smallest amount to include.
+    pub min_amount: Option<Money>,
+
+    /// the largest amount to include.
+    pub max_amount: Option<Money>
+}
+
+impl Filter {
+    /// create a filter that matches every record.
+    pub fn new() -> Filter {
+        Filter::default()
+    }
+
+    /// only include records on or after the given day.
+    pub fn with_from(mut self, from: NaiveDate) -> Filter {
+        self.from = Some(from);
+        self
+    }
+
+    /// only include records on or before the given day.
+    pub fn with_to(mut self, to: NaiveDate) -> Filter {
+        self.to = Some(to);
+        self
+    }
+
+    /// only include records in the given category.
+    pub fn with_category(mut self, category: &str) -> Filter {
+        self.category = Some(String::from(category));
+        self
+    }
+
+    /// only include records whose vendor contains the given text.
+    pub fn with_vendor(mut self, vendor: &str) -> Filter {
+        self.vendor = Some(String::from(vendor));
+        self
+    }
+
+    /// only include records of the given type.
+    pub fn with_type(mut self, transaction_type: TransactionType) -> Filter {
+        self.transaction_type = Some(transaction_type);
+        self
+    }
+
+    /// only include reconciled records, or only unreconciled ones.
+    pub fn with_reconciled(mut self, reconciled: bool) -> Filter {
+        self.reconciled = Some(reconciled);
+        self
+    }
+
+    /// only include records with an amount from min to max, including both.
+    pub fn with_amount_range(mut self, min: Option<Money>, max: Option<Money>) -> Filter {
+        self.min_amount = min;
+        self.max_amount = max;
+        self
+    }
+
+    /// whether the given record matches every condition.
+    pub fn matches(&self, record: &Record) -> bool {
+        let transaction = &record.transaction;
+        let date = transaction.date.date_naive();
+
+        self.from.is_none_or(|from| date >= from) &&
+        self.to.is_none_or(|to| date <= to) &&
+        self.category.as_ref().is_none_or(|category| transaction.category_amounts().iter().any(|(record_category, _)| record_category.is_some_and(|record_category| record_category.eq_ignore_ascii_case(category)))) &&
+        self.vendor.as_ref().is_none_or(|vendor| transaction.vendor.to_lowercase().contains(&vendor.to_lowercase())) &&
+        self.transaction_type.as_ref().is_none_or(|transaction_type| transaction.transaction_type == *transaction_type) &&
+        self.reconciled.is_none_or(|reconciled| transaction.is_reconciled == reconciled) &&
+        self.min_amount.is_none_or(|min_amount| transaction.amount >= min_amount) &&
+        self.max_amount.is_none_or(|max_amount| transaction.amount <= max_amount)
+    }
+
+    /// the records that match, in the order they were given.
+    pub fn apply<'a>(&self, records: &'a [Record]) -> Vec<&'a Record> {
+        records.iter().filter(|record| self.matches(record)).collect()
+    }
+}
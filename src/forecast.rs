@@ -0,0 +1,111 @@
+// import custom types, so that balances can be projected.
+use crate::{ money::Money, record::Record, schedule::Schedule };
+
+// import chrono, so that the forecast can go day by day.
+use chrono::NaiveDate;
+
+// import HashSet, so that scheduled records already in the register are not counted twice.
+use std::collections::HashSet;
+
+/// Represents the projected balance at the end of a single day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForecastDay {
+    /// the day.
+    pub date: NaiveDate,
+
+    /// the balance at the end of the day.
+    pub balance: Money,
+
+    /// the records dated on the day, both from the register and from schedules.
+    pub records: Vec<Record>
+}
+
+/**
+ * Projects the balance day by day, from the records in a register, including ones dated in the future, and the occurrences of schedules.
+ * # Example
+ * ```
+ * use bcheck::{ Forecast, Record };
+ * use chrono::{ Days, Local };
+ *
+ * let records = vec![Record::new()];
+ * let today = Local::now().date_naive();
+ *
+ * if let Some(lowest) = Forecast::new(&records, today, today + Days::new(30)).lowest() {
+ *     println!("lowest balance is {} on {}", lowest.balance, lowest.date)
+ * }
+ * ```
+ */
+#[derive(Clone, Debug)]
+pub struct Forecast<'a> {
+    records: &'a [Record],
+    schedules: &'a [Schedule],
+    opening_balance: Money,
+    start: NaiveDate,
+    end: NaiveDate
+}
+
+impl<'a> Forecast<'a> {
+    /// create a forecast over the given records from start to end, including both days.
+    pub fn new(records: &'a [Record], start: NaiveDate, end: NaiveDate) -> Forecast<'a> {
+        Forecast {
+            records,
+            schedules: &[],
+            opening_balance: Money::ZERO,
+            start,
+            end
+        }
+    }
+
+    /**
+     * add the occurrences of the given schedules that have not been posted yet.
+     * Occurrences that are already in the register are only counted once.
+     */
+    pub fn with_schedules(mut self, schedules: &'a [Schedule]) -> Forecast<'a> {
+        self.schedules = schedules;
+        self
+    }
+
+    /// set the balance the account had before the first record.
+    pub fn with_opening_balance(mut self, opening_balance: Money) -> Forecast<'a> {
+        self.opening_balance = opening_balance;
+        self
+    }
+
+    /// the balance at the end of every day in the forecast, in order.
+    pub fn days(&self) -> Vec<ForecastDay> {
+        let ids: HashSet<&str> = self.records.iter().map(|record| record.id.as_str()).collect();
+
+        let mut upcoming: Vec<Record> = self.records.iter().filter(|record| record.transaction.date.date_naive() >= self.start).cloned().collect();
+
+        for schedule in self.schedules {
+            upcoming.extend(schedule.records_between(self.start, self.end).into_iter().filter(|record| !ids.contains(record.id.as_str())));
+        }
+
+        // keep the order records were given in for records on the same day.
+        upcoming.sort_by_key(|record| record.transaction.date.date_naive());
+
+        let mut balance = self.records.iter().filter(|record| record.transaction.date.date_naive() < self.start).fold(self.opening_balance, |balance, record| balance.saturating_add(record.transaction.signed_amount()));
+        let mut upcoming = upcoming.into_iter().peekable();
+
+        self.start.iter_days().take_while(|date| *date <= self.end).map(|date| {
+            let mut records: Vec<Record> = vec![];
+
+            while let Some(record) = upcoming.next_if(|record| record.transaction.date.date_naive() == date) {
+                balance = balance.saturating_add(record.transaction.signed_amount());
+                records.push(record);
+            }
+
+            ForecastDay { date, balance, records }
+        }).collect()
+    }
+
+    /// the day with the lowest balance, which is the earliest one if several days share it.
+    pub fn lowest(&self) -> Option<ForecastDay> {
+        self.days().into_iter().reduce(|lowest, day| if day.balance < lowest.balance { day } else { lowest })
+    }
+
+    /// the first day the balance goes below zero.
+    pub fn first_negative(&self) -> Option<ForecastDay> {
+        self.days().into_iter().find(|day| day.balance.is_negative())
+    }
+}
@@ -0,0 +1,209 @@
+// import custom types, so that records can be read and written in each format.
+use crate::{ csv_format::CsvFormat, error::Error, ics, lost_field::LostField, qif, record::Record, save_vec::{ Save, write_file } };
+
+// import things needed to display and parse formats, and to read and write data.
+use std::{ fmt, io::Write, path::Path, str::FromStr };
+
+/// Represents a file format that records can be converted from or to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// JSON, as written by BCheckbook.
+    Bcheck,
+
+    /// tab delimited values, using the default layout.
+    Tsv,
+
+    /// comma separated values, using the default layout.
+    Csv,
+
+    /// Quicken Interchange Format.
+    Qif,
+
+    /// Open Financial Exchange, which can only be read.
+    Ofx,
+
+    /// iCalendar, which can only be written.
+    Ics
+}
+
+impl Format {
+    /// the name used for the format, which is also the usual file extension.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Bcheck => "bcheck",
+            Self::Tsv => "tsv",
+            Self::Csv => "csv",
+            Self::Qif => "qif",
+            Self::Ofx => "ofx",
+            Self::Ics => "ics"
+        }
+    }
+
+    /// whether records can be read from the format.
+    pub fn can_read(&self) -> bool {
+        *self != Self::Ics
+    }
+
+    /// whether records can be written in the format.
+    pub fn can_write(&self) -> bool {
+        *self != Self::Ofx
+    }
+
+    /// the format that goes with the extension of the given path, ignoring case.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Format> {
+        match path.as_ref().extension()?.to_str()?.to_lowercase().as_str() {
+            "bcheck" | "json" => Some(Self::Bcheck),
+            "tsv" => Some(Self::Tsv),
+            "csv" => Some(Self::Csv),
+            "qif" => Some(Self::Qif),
+            "ofx" | "qfx" => Some(Self::Ofx),
+            "ics" => Some(Self::Ics),
+            _ => None
+        }
+    }
+
+    /**
+     * guess the format from the start of some data.
+     * Delimited data is only recognized by the delimiter found in its first line.
+     */
+    pub fn sniff(content: &[u8]) -> Option<Format> {
+        let text = String::from_utf8_lossy(&content[..content.len().min(1024)]);
+        let text = text.trim_start_matches('\u{feff}').trim_start();
+        let upper_text = text.to_uppercase();
+        let first_line = text.lines().next().unwrap_or("");
+
+        if text.starts_with(['[', '{']) {
+            Some(Self::Bcheck)
+        } else if upper_text.starts_with("!TYPE") || upper_text.starts_with("!ACCOUNT") || upper_text.starts_with("!OPTION") {
+            Some(Self::Qif)
+        } else if upper_text.starts_with("OFXHEADER") || upper_text.contains("<OFX>") {
+            Some(Self::Ofx)
+        } else if upper_text.starts_with("BEGIN:VCALENDAR") {
+            Some(Self::Ics)
+        } else if first_line.contains('\t') {
+            Some(Self::Tsv)
+        } else if first_line.contains(',') {
+            Some(Self::Csv)
+        } else {
+            None
+        }
+    }
+
+    /// work out the format of a file from its extension, or from its content when the extension is not known.
+    pub fn detect<P: AsRef<Path>>(path: P, content: &[u8]) -> Option<Format> {
+        Format::from_path(path).or_else(|| Format::sniff(content))
+    }
+
+    /// read records from data in the format.
+    pub fn read(&self, content: &[u8]) -> Result<Vec<Record>, Error> {
+        match self {
+            Self::Bcheck => Record::from_reader(content),
+            Self::Tsv => Record::from_tsv_reader(content),
+            Self::Csv => Record::from_csv_reader(content, &CsvFormat::default()),
+            Self::Qif => Record::from_qif_reader(content),
+            Self::Ofx => Record::from_ofx_reader(content),
+            Self::Ics => Err(Error::Invalid { value: self.to_string(), expected: "a format that can be read" })
+        }
+    }
+
+    /// write records in the format.
+    pub fn write<W: Write>(&self, records: &[Record], mut writer: W) -> Result<(), Error> {
+        match self {
+            Self::Bcheck => records.to_writer(&mut writer),
+            Self::Tsv => records.to_tsv_writer(&mut writer),
+            Self::Csv => CsvFormat::default().write(records, writer),
+            Self::Qif => Ok(write!(writer, "{}", qif::string_from_records(records))?),
+            Self::Ics => Ok(write!(writer, "{}", ics::string_from_records(records))?),
+            Self::Ofx => Err(Error::Invalid { value: self.to_string(), expected: "a format that can be written" })
+        }
+    }
+
+    /// save records in the format to the given path.
+    pub fn save<P: AsRef<Path>>(&self, records: &[Record], path: P) -> Result<(), Error> {
+        write_file(&path, |output| self.write(records, output))
+    }
+
+    /**
+     * the parts of every record that the format never keeps, such as ids in QIF files.
+     * These are left out of lost_fields(), since they say nothing about a particular record.
+     */
+    pub fn always_lost_fields(&self) -> Vec<LostField> {
+        match self {
+            Self::Qif => vec![LostField::Id],
+            _ => vec![]
+        }
+    }
+
+    /// the parts of a record that would not survive being written in the format and read back, other than the ones in always_lost_fields().
+    pub fn lost_fields(&self, record: &Record) -> Vec<LostField> {
+        let transaction = &record.transaction;
+        let mut lost_fields: Vec<LostField> = vec![];
+
+        // line breaks end rows and lines, and tabs end fields, in the formats that are not quoted, and QIF drops spaces around each value.
+        let unsafe_characters: &[char] = match self {
+            Self::Tsv => &['\t', '\r', '\n'],
+            Self::Qif => &['\r', '\n'],
+            _ => &[]
+        };
+        let breaks = |text: &str| text.contains(unsafe_characters) || (*self == Self::Qif && text.trim() != text);
+
+        if transaction.category.as_deref().is_some_and(&breaks) {
+            lost_fields.push(LostField::Category);
+        }
+
+        if breaks(&transaction.vendor) {
+            lost_fields.push(LostField::Vendor);
+        }
+
+        if breaks(&transaction.memo) {
+            lost_fields.push(LostField::Memo);
+        }
+
+        // delimited formats write a negative amount with a minus sign that cannot be read back, while QIF flips the type instead.
+        if transaction.amount.is_negative() && matches!(self, Self::Tsv | Self::Csv | Self::Qif) {
+            lost_fields.push(LostField::Amount);
+        }
+
+        if matches!(self, Self::Ics) && transaction.is_reconciled {
+            lost_fields.push(LostField::Reconciled);
+        }
+
+        let keeps_splits = match self {
+            Self::Bcheck => true,
+            Self::Qif => !transaction.splits.iter().any(|split| split.category.as_deref().is_some_and(&breaks) || breaks(&split.memo)),
+            _ => false
+        };
+
+        if !transaction.splits.is_empty() && !keeps_splits {
+            lost_fields.push(LostField::Splits);
+        }
+
+        if record.transfer_id.is_some() && *self != Self::Bcheck {
+            lost_fields.push(LostField::TransferId);
+        }
+
+        lost_fields
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().trim_start_matches('.') {
+            "bcheck" | "json" => Ok(Self::Bcheck),
+            "tsv" => Ok(Self::Tsv),
+            "csv" => Ok(Self::Csv),
+            "qif" => Ok(Self::Qif),
+            "ofx" | "qfx" => Ok(Self::Ofx),
+            "ics" => Ok(Self::Ics),
+            _ => Err(Error::Invalid { value: s.to_string(), expected: "bcheck, tsv, csv, qif, ofx, or ics" })
+        }
+    }
+}
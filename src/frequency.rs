@@ -0,0 +1,63 @@
+// import chrono, so that dates can be worked out.
+use chrono::{ Datelike, Days, Months, NaiveDate, Weekday };
+
+// import Serde, so that frequencies can be stored with schedules.
+use serde::{ Serialize, Deserialize };
+
+/// Represents how often a scheduled transaction happens.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Frequency {
+    /// every week, on the same day of the week as the start.
+    Weekly,
+
+    /// every other week, on the same day of the week as the start.
+    Biweekly,
+
+    /// every month on the given day, or the last day of the month for months that are too short.
+    MonthlyOnDay { day: u32 },
+
+    /// every month on the last day from Monday to Friday.
+    LastBusinessDay,
+
+    /// every year on the same day as the start, which is February 28 in years without a February 29.
+    Yearly
+}
+
+impl Frequency {
+    /**
+     * the date of the occurrence at the given position, counting from the start, before checking that it is not before the start.
+     * Monthly frequencies can fall before the start in the first month, so callers skip those.
+     */
+    pub(crate) fn candidate(&self, start: NaiveDate, index: u32) -> Option<NaiveDate> {
+        match self {
+            Self::Weekly => start.checked_add_days(Days::new(7 * index as u64)),
+            Self::Biweekly => start.checked_add_days(Days::new(14 * index as u64)),
+            Self::MonthlyOnDay { day } => {
+                let month = month_start(start)?.checked_add_months(Months::new(index))?;
+
+                Some(month.with_day((*day).clamp(1, 31)).unwrap_or_else(|| last_day(month)))
+            },
+            Self::LastBusinessDay => {
+                let mut date = last_day(month_start(start)?.checked_add_months(Months::new(index))?);
+
+                while matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+                    date = date.pred_opt()?;
+                }
+
+                Some(date)
+            },
+            Self::Yearly => start.checked_add_months(Months::new(12 * index))
+        }
+    }
+}
+
+// the first day of the month of the given date.
+fn month_start(date: NaiveDate) -> Option<NaiveDate> {
+    date.with_day(1)
+}
+
+// the last day of the month that starts on the given date.
+fn last_day(month: NaiveDate) -> NaiveDate {
+    month.checked_add_months(Months::new(1)).and_then(|next_month| next_month.pred_opt()).unwrap_or(NaiveDate::MAX)
+}
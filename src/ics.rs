@@ -0,0 +1,84 @@
+// import custom types, so that records can become calendar events.
+use crate::{ record::Record, transaction_type::TransactionType };
+
+// import chrono, so that events can be dated and stamped.
+use chrono::{ Days, Utc };
+
+/**
+ * turn records into an iCalendar file, with an all day event for each one.
+ * Each event shows the vendor and amount in its title, and the category, memo, and check number in its description,
+ * so that expected bills show up in calendar apps.
+ */
+pub(crate) fn string_from_records(records: &[Record]) -> String {
+    let time_stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines: Vec<String> = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//bcheck//bcheck//EN"),
+        String::from("CALSCALE:GREGORIAN")
+    ];
+
+    for record in records {
+        let transaction = &record.transaction;
+        let date = transaction.date.date_naive();
+        let signed_amount = transaction.signed_amount();
+
+        let mut description = vec![format!("Amount: {}", signed_amount)];
+
+        if let Some(category) = &transaction.category {
+            description.push(format!("Category: {}", category));
+        }
+
+        if let Some(check_number) = transaction.check_number {
+            description.push(format!("Check: {}", check_number));
+        }
+
+        if !transaction.memo.is_empty() {
+            description.push(format!("Memo: {}", transaction.memo));
+        }
+
+        lines.push(String::from("BEGIN:VEVENT"));
+        lines.push(format!("UID:{}@bcheck", record.id));
+        lines.push(format!("DTSTAMP:{}", time_stamp));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+        lines.push(format!("DTEND;VALUE=DATE:{}", date.checked_add_days(Days::new(1)).unwrap_or(date).format("%Y%m%d")));
+        lines.push(format!("SUMMARY:{}", escape(&format!("{} {}{}", transaction.vendor, if transaction.transaction_type == TransactionType::Deposit { "+" } else { "" }, signed_amount))));
+        lines.push(format!("DESCRIPTION:{}", escape(&description.join("\n"))));
+
+        if let Some(category) = &transaction.category {
+            lines.push(format!("CATEGORIES:{}", escape(category)));
+        }
+
+        lines.push(String::from("TRANSP:TRANSPARENT"));
+        lines.push(String::from("END:VEVENT"));
+    }
+
+    lines.push(String::from("END:VCALENDAR"));
+
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+// escape the characters that have meaning in iCalendar text.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace("\r\n", "\\n").replace(['\r', '\n'], "\\n")
+}
+
+// end a line, splitting it so that no line is longer than 75 bytes, as iCalendar requires.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+
+        folded.push(c);
+        length += c.len_utf8();
+    }
+
+    folded.push_str("\r\n");
+
+    folded
+}
@@ -0,0 +1,285 @@
+// import custom types, so that rows can be turned into records and back.
+use crate::{ column::Column, error::Error, record::{ Record, default_id }, transaction::{ Transaction, transaction_date_format::FORMAT }, transaction_type::TransactionType, LocalDateTimeStringExt };
+
+// import chrono, so that a date can be given when a layout does not have one.
+use chrono::Local;
+
+// import Money, so that amounts can be stored exactly.
+use crate::money::Money;
+
+/**
+ * Describes the order of the columns in delimited data, and whether a header row is included.
+ * The default layout matches the TSV files made by BCheckbook, which have no header and the columns
+ * id, date, check number, reconciled, category, vendor, memo, deposit, and withdrawal.
+ * # Example
+ * ```
+ * use bcheck::{ Column, Layout };
+ *
+ * let layout = Layout::new(vec![Column::Date, Column::Vendor, Column::Withdrawal, Column::Deposit]).with_header(true);
+ * ```
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layout {
+    columns: Vec<Column>,
+    header: bool
+}
+
+impl Layout {
+    /// create a layout with the given columns and no header row.
+    pub fn new(columns: Vec<Column>) -> Layout {
+        Layout {
+            columns,
+            header: false
+        }
+    }
+
+    /**
+     * create a layout from a header row, such as one made by a spreadsheet.
+     * Columns with names that are not recognized are ignored.
+     */
+    pub fn from_header(line: &str) -> Layout {
+        let fields: Vec<&str> = line.split('\t').collect();
+
+        Layout::from_header_fields(&fields)
+    }
+
+    /// create a layout from the already separated fields of a header row.
+    pub(crate) fn from_header_fields(fields: &[&str]) -> Layout {
+        Layout {
+            columns: fields.iter().map(|name| name.parse().unwrap_or(Column::Ignored)).collect(),
+            header: true
+        }
+    }
+
+    /// set whether a header row is written out, and whether the first row must be a header when reading.
+    pub fn with_header(mut self, header: bool) -> Layout {
+        self.header = header;
+        self
+    }
+
+    /**
+     * replace the deposit and withdrawal columns with a single signed amount column,
+     * where withdrawals are negative. The amount column takes the place of the deposit column.
+     */
+    pub fn with_signed_amount(mut self) -> Layout {
+        self.columns = self.columns.into_iter().filter(|column| *column != Column::Withdrawal).map(|column| if column == Column::Deposit {
+            Column::Amount
+        } else {
+            column
+        }).collect();
+
+        self
+    }
+
+    /// the columns, in the order they appear.
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// whether the layout includes a header row.
+    pub fn has_header(&self) -> bool {
+        self.header
+    }
+
+    /// the layout used by a transaction on its own, which is the default layout without an id.
+    pub(crate) fn transaction() -> Layout {
+        Layout::new(Layout::default().columns.into_iter().filter(|column| *column != Column::Id).collect())
+    }
+
+    /// the names to put in a header row.
+    pub(crate) fn header_fields(&self) -> Vec<String> {
+        self.columns.iter().map(|column| column.name().to_string()).collect()
+    }
+
+    /**
+     * check whether a row looks like a header, which is the case when most of the fields that are filled in name a column,
+     * or when the field where the date should be is text instead of a date, so that exports with extra columns are still recognized.
+     * A vendor or memo that happens to be a column name, like Credit, or a date that was typed wrong, still leaves the row to be read as a row.
+     */
+    pub(crate) fn is_header(&self, fields: &[&str]) -> bool {
+        let filled_fields: Vec<&str> = fields.iter().map(|field| field.trim()).filter(|field| !field.is_empty()).collect();
+        let names = filled_fields.iter().filter(|field| field.parse::<Column>().is_ok()).count();
+
+        let is_date_text = self.columns.iter().position(|column| *column == Column::Date).and_then(|index| fields.get(index)).map(|date| date.trim()).is_some_and(|date| {
+            !date.is_empty() && !date.chars().any(|c| c.is_ascii_digit()) && date.local_datetime().is_err()
+        });
+
+        names * 2 > filled_fields.len() || is_date_text
+    }
+
+    /**
+     * create a record from the fields of a row.
+     * Columns missing from the layout get default values, but the row must have every column in the layout,
+     * except the last one, since spreadsheets tend to leave off empty cells at the end of a row.
+     */
+    pub(crate) fn record_from_fields(&self, fields: &[&str]) -> Result<Record, Error> {
+        if fields.len() + 1 < self.columns.len() {
+            return Err(Error::Field { line: None, column: fields.len() + 1, value: String::new(), source: None })
+        }
+
+        let value = |column: Column| -> Option<(usize, &str)> {
+            let index = self.columns.iter().position(|c| *c == column)?;
+
+            Some((index, fields.get(index).map(|field| field.trim()).unwrap_or("")))
+        };
+
+        let invalid = |index: usize, source: Error| Error::Field { line: None, column: index + 1, value: fields.get(index).unwrap_or(&"").to_string(), source: Some(Box::new(source)) };
+
+        let id = match value(Column::Id) {
+            Some((_, id)) if !id.is_empty() => id.to_string(),
+            _ => default_id()
+        };
+
+        let date = match value(Column::Date) {
+            Some((index, date)) => date.local_datetime().map_err(|error| invalid(index, error))?,
+            None => Local::now()
+        };
+
+        let check_number = match value(Column::CheckNumber) {
+            Some((index, check_number)) if !check_number.is_empty() => Some(check_number.parse::<u32>().map_err(|_| invalid(index, Error::Invalid { value: check_number.to_string(), expected: "a check number" }))?),
+            _ => None
+        };
+
+        let is_reconciled = match value(Column::Reconciled) {
+            Some((_, "Y" | "y")) => true,
+            Some((_, "N" | "n" | "")) | None => false,
+            Some((index, reconciled)) => return Err(invalid(index, Error::Invalid { value: reconciled.to_string(), expected: "Y or N" }))
+        };
+
+        // text fields are kept as they are, so that spacing in a memo is not lost.
+        let text = |column: Column| -> String {
+            self.columns.iter().position(|c| *c == column).and_then(|index| fields.get(index)).map(|field| field.to_string()).unwrap_or_default()
+        };
+
+        let deposit = value(Column::Deposit).filter(|(_, amount)| !amount.is_empty());
+        let withdrawal = value(Column::Withdrawal).filter(|(_, amount)| !amount.is_empty());
+
+        let signed_amount = value(Column::Amount).filter(|(_, amount)| !amount.is_empty());
+
+        let ((amount_index, amount_string), transaction_type) = match (deposit, withdrawal, signed_amount) {
+            (Some(deposit), None, _) => (deposit, TransactionType::Deposit),
+            (None, Some(withdrawal), _) => (withdrawal, TransactionType::Withdrawal),
+            (Some(_), Some((index, withdrawal)), _) => return Err(invalid(index, Error::Invalid { value: withdrawal.to_string(), expected: "no withdrawal, since a deposit was given" })),
+            (None, None, Some((index, amount))) => match amount.strip_prefix('-') {
+                Some(withdrawal) => ((index, withdrawal), TransactionType::Withdrawal),
+                None => ((index, amount), TransactionType::Deposit)
+            },
+            (None, None, None) => return Err(match value(Column::Deposit).or(value(Column::Withdrawal)).or(value(Column::Amount)) {
+                Some((index, _)) => invalid(index, Error::Invalid { value: String::new(), expected: "a deposit or withdrawal amount" }),
+                None => Error::Invalid { value: String::new(), expected: "a layout with an amount column" }
+            })
+        };
+
+        let amount = match amount_string.parse::<Money>() {
+            Ok(amount) if !amount.is_negative() => amount,
+            Ok(_) => return Err(invalid(amount_index, Error::Invalid { value: amount_string.to_string(), expected: "an amount that is not negative" })),
+            Err(error) => return Err(invalid(amount_index, Error::Amount { value: amount_string.to_string(), source: error }))
+        };
+
+        let category = text(Column::Category);
+
+        Ok(Record {
+            id,
+            transaction: Transaction {
+                date,
+                check_number,
+                category: if category.is_empty() {
+                    None
+                } else {
+                    Some(category)
+                },
+                vendor: text(Column::Vendor),
+                memo: text(Column::Memo),
+                amount,
+                transaction_type,
+                is_reconciled,
+                splits: vec![]
+            },
+            transfer_id: None
+        })
+    }
+
+    /// turn a record into the fields of a row.
+    pub(crate) fn fields_from_record(&self, record: &Record) -> Vec<String> {
+        let transaction = &record.transaction;
+
+        self.columns.iter().map(|column| match column {
+            Column::Id => record.id.clone(),
+            Column::Date => transaction.date.format(FORMAT).to_string(),
+            Column::CheckNumber => transaction.check_number.map(|check_number| check_number.to_string()).unwrap_or_default(),
+            Column::Reconciled => String::from(if transaction.is_reconciled { "Y" } else { "N" }),
+            Column::Category => transaction.category.clone().unwrap_or_default(),
+            Column::Vendor => transaction.vendor.clone(),
+            Column::Memo => transaction.memo.clone(),
+            Column::Deposit if transaction.transaction_type == TransactionType::Deposit => format!("{}", transaction.amount),
+            Column::Withdrawal if transaction.transaction_type == TransactionType::Withdrawal => format!("{}", transaction.amount),
+            Column::Amount if transaction.transaction_type == TransactionType::Withdrawal => format!("-{}", transaction.amount),
+            Column::Amount => format!("{}", transaction.amount),
+            _ => String::new()
+        }).collect()
+    }
+
+    /**
+     * turn rows into records, collecting the rows that could not be read.
+     * Each row comes with the line it started on. Blank rows are skipped, and if the first row is a header, it is skipped too.
+     * When the layout has the default columns, the columns the header names are used instead, since the header describes the data better than the defaults.
+     * A layout with its own columns and a header keeps its columns, so that they can be given for a header with names that are not recognized.
+     */
+    pub(crate) fn records_from_rows<I>(&self, rows: I) -> Result<(Vec<Record>, Vec<Error>), Error> where I: IntoIterator<Item = Result<(usize, Vec<String>), Error>> {
+        let mut layout = self.clone();
+        let has_own_columns = self.columns != Layout::default().columns;
+        let mut records: Vec<Record> = vec![];
+        let mut errors: Vec<Error> = vec![];
+        let mut is_first_row = true;
+
+        for row in rows {
+            let (line, fields) = row?;
+            let fields: Vec<&str> = fields.iter().map(|field| field.as_str()).collect();
+
+            if fields.iter().all(|field| field.trim().is_empty()) {
+                continue
+            }
+
+            if is_first_row {
+                is_first_row = false;
+
+                if layout.has_header() && has_own_columns {
+                    continue
+                }
+
+                if layout.has_header() || layout.is_header(&fields) {
+                    let header_layout = Layout::from_header_fields(&fields);
+
+                    if header_layout.columns().iter().any(|column| *column != Column::Ignored) {
+                        layout = header_layout;
+                    }
+
+                    continue
+                }
+            }
+
+            match layout.record_from_fields(&fields) {
+                Ok(record) => records.push(record),
+                Err(error) => errors.push(error.at_line(line))
+            }
+        }
+
+        Ok((records, errors))
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout::new(vec![
+            Column::Id,
+            Column::Date,
+            Column::CheckNumber,
+            Column::Reconciled,
+            Column::Category,
+            Column::Vendor,
+            Column::Memo,
+            Column::Deposit,
+            Column::Withdrawal
+        ])
+    }
+}
@@ -22,16 +22,89 @@ mod transaction;
 mod record;
 mod save_vec;
 mod transaction_type_parse_error;
+mod error;
+mod column;
+mod layout;
+mod csv_format;
+mod ofx;
+mod qif;
+mod running_balance;
+mod money;
+mod money_parse_error;
+mod register;
+mod reconciliation;
+mod discrepancy;
+mod period;
+mod report;
+mod companion;
+mod rollover;
+mod budget;
+mod budget_report;
+mod frequency;
+mod schedule_end;
+mod schedule;
+mod rrule;
+mod ics;
+mod forecast;
+mod account_type;
+mod account;
+mod workbook;
+mod transfer_issue;
+mod split;
+mod filter;
+mod format;
+mod lost_field;
+mod conversion;
+
+#[cfg(feature = "sqlite")]
+mod sqlite_store;
 
 pub use crate::transaction::Transaction as Transaction;
 pub use crate::transaction_type::TransactionType as TransactionType;
 pub use crate::record::Record as Record;
 pub use crate::save_vec::Save as Save;
+pub use crate::error::Error as Error;
+pub use crate::column::Column as Column;
+pub use crate::layout::Layout as Layout;
+pub use crate::csv_format::CsvFormat as CsvFormat;
+pub use crate::running_balance::RunningBalance as RunningBalance;
 pub use crate::local_datetime_from_string::LocalDateTimeExt as LocalDateTimeStringExt;
 pub use crate::transaction::transaction_date_format::is_proper_format as is_proper_date_format;
-pub use ordered_float::OrderedFloat as OrderedFloat;
+pub use crate::local_datetime_from_string::local_midnight as local_midnight;
+pub use crate::money::Money as Money;
+pub use crate::money_parse_error::MoneyParseError as MoneyParseError;
+pub use crate::register::Register as Register;
+pub use crate::reconciliation::Reconciliation as Reconciliation;
+pub use crate::discrepancy::Discrepancy as Discrepancy;
+pub use crate::discrepancy::DiscrepancyFinder as DiscrepancyFinder;
+pub use crate::period::Period as Period;
+pub use crate::report::Report as Report;
+pub use crate::report::ReportRow as ReportRow;
+pub use crate::rollover::Rollover as Rollover;
+pub use crate::budget::Budget as Budget;
+pub use crate::budget_report::BudgetReport as BudgetReport;
+pub use crate::budget_report::BudgetRow as BudgetRow;
+pub use crate::frequency::Frequency as Frequency;
+pub use crate::schedule_end::ScheduleEnd as ScheduleEnd;
+pub use crate::schedule::Schedule as Schedule;
+pub use crate::forecast::Forecast as Forecast;
+pub use crate::forecast::ForecastDay as ForecastDay;
+pub use crate::account_type::AccountType as AccountType;
+pub use crate::account::Account as Account;
+pub use crate::workbook::Workbook as Workbook;
+pub use crate::transfer_issue::TransferIssue as TransferIssue;
+pub use crate::split::Split as Split;
+pub use crate::filter::Filter as Filter;
+pub use crate::format::Format as Format;
+pub use crate::lost_field::LostField as LostField;
+pub use crate::conversion::Conversion as Conversion;
+
+#[cfg(feature = "sqlite")]
+pub use crate::sqlite_store::SqliteStore as SqliteStore;
 
 #[cfg(test)]
+// the original tests are kept as they were written, so lints added since then are allowed.
+#[allow(clippy::unnecessary_cast, clippy::nonminimal_bool, clippy::single_component_path_imports)]
 mod tests {
     use crate::record::Record;
     use crate::transaction::Transaction;
@@ -40,6 +113,9 @@ mod tests {
     use crate::LocalDateTimeStringExt;
     use pretty_assertions::{assert_eq};
     use crate::save_vec::Save;
+    use crate::error::Error;
+    use crate::{ column::Column, layout::Layout, csv_format::CsvFormat, running_balance::RunningBalance, money::Money, money_parse_error::MoneyParseError, register::Register, reconciliation::Reconciliation, discrepancy::{ Discrepancy, DiscrepancyFinder }, period::Period, report::Report, rollover::Rollover, budget::Budget, budget_report::BudgetReport, frequency::Frequency, schedule_end::ScheduleEnd, schedule::Schedule, forecast::Forecast, account_type::AccountType, account::Account, workbook::Workbook, transfer_issue::TransferIssue, split::Split, filter::Filter, format::Format, lost_field::LostField, conversion::Conversion };
+    use chrono::NaiveDate;
     use std::str::FromStr;
 
     use serde_json;
@@ -175,6 +251,29 @@ mod tests {
         assert!(!"2021-7-26".local_datetime().is_err())
     }
 
+    #[test]
+    fn parse_impossible_date_errors_out() {
+        assert!(matches!("2021-2-30".local_datetime(), Err(Error::Date(_))))
+    }
+
+    #[test]
+    fn missing_file_reports_path() {
+        let path = std::env::temp_dir().join("does_not_exist.bcheck");
+
+        match Record::from_file(&path) {
+            Err(Error::Io { path: Some(error_path), .. }) => assert_eq!(error_path, path),
+            _ => panic!("expected an I/O error naming the file")
+        }
+    }
+
+    #[test]
+    fn invalid_json_is_json_error() {
+        let path = std::env::temp_dir().join("invalid.bcheck");
+        std::fs::write(&path, "not json").unwrap();
+
+        assert!(matches!(Record::from_file(&path), Err(Error::Json { path: Some(_), .. })))
+    }
+
     #[test]
     fn deposit_transaction_to_string() {
         let expected_string = String::from("2021-07-08\t1260\tY\tOpening Balance\tSam Hill Credit Union\tOpen Account\t500.00\t");
@@ -225,6 +324,1334 @@ mod tests {
         assert_eq!(Record::from_string(transaction_string), expected_record)
     }
 
+    #[test]
+    fn load_records_from_reader() {
+        let json = r#"[{ "id": "FF04C3DC-F0FE-472E-8737-0F4034C049F0", "transaction": { "amount": 500, "vendor": "Sam Hill Credit Union", "type": "deposit", "date": "2021-07-08" } }]"#;
+
+        let records = Record::from_reader(json.as_bytes()).unwrap();
+
+        assert_eq!(records[0].transaction.vendor, "Sam Hill Credit Union")
+    }
+
+    #[test]
+    fn write_and_read_json_in_memory() {
+        let records: Vec<Record> = vec![
+            Record::from("FF04C3DC-F0FE-472E-8737-0F4034C049F0", Transaction::from(Some("2021-7-8"), Some(1260), None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap()),
+            Record::from("1422CBC6-7B0B-4584-B7AB-35167CC5647B", Transaction::from(Some("2021-7-8"), None, None, "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        let mut buffer: Vec<u8> = vec![];
+        records.to_writer(&mut buffer).unwrap();
+
+        assert_eq!(Record::from_reader(buffer.as_slice()).unwrap(), records)
+    }
+
+    #[test]
+    fn write_and_read_tsv_in_memory() {
+        let records: Vec<Record> = vec![
+            Record::from("FF04C3DC-F0FE-472E-8737-0F4034C049F0", Transaction::from(Some("2021-7-8"), Some(1260), None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap()),
+            Record::from("1422CBC6-7B0B-4584-B7AB-35167CC5647B", Transaction::from(Some("2021-7-8"), None, None, "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        let mut buffer: Vec<u8> = vec![];
+        records.to_tsv_writer(&mut buffer).unwrap();
+
+        assert_eq!(Record::from_tsv_reader(buffer.as_slice()).unwrap(), records)
+    }
+
+    #[test]
+    fn parse_record_from_string() {
+        let transaction_string = "FF04C3DC-F0FE-472E-8737-0F4034C049F0\t2021-07-08\t\tN\tGifts\tFake Street Electronics\tHead set\t\t200.00";
+        let expected_record = Record::from("FF04C3DC-F0FE-472E-8737-0F4034C049F0", Transaction::from(Some("2021-7-8"), None, Some("Gifts"), "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap());
+
+        let record: Record = transaction_string.parse().unwrap();
+
+        assert_eq!(record, expected_record);
+        assert_eq!(record.transaction, expected_record.transaction)
+    }
+
+    #[test]
+    fn parse_short_row_reports_missing_column() {
+        match Transaction::try_from("2021-07-08\t1260\tY") {
+            Err(Error::Field { line: None, column: 4, source: None, .. }) => (),
+            result => panic!("expected missing column 4, got {:?}", result)
+        }
+    }
+
+    #[test]
+    fn parse_row_with_bad_date_reports_column() {
+        match "FF04C3DC-F0FE-472E-8737-0F4034C049F0\t2021-13-08\t\tN\t\tFake Street Electronics\t\t\t200.00".parse::<Record>() {
+            Err(Error::Field { column: 2, value, .. }) => assert_eq!(value, "2021-13-08"),
+            result => panic!("expected bad date in column 2, got {:?}", result)
+        }
+    }
+
+    #[test]
+    fn parse_row_with_bad_amount_reports_column() {
+        match "2021-07-08\t\tN\t\tFake Street Electronics\t\t\tabc".parse::<Transaction>() {
+            Err(Error::Field { column: 8, source: Some(source), .. }) => assert!(matches!(*source, Error::Amount { .. })),
+            result => panic!("expected bad amount in column 8, got {:?}", result)
+        }
+    }
+
+    #[test]
+    fn tsv_reader_reports_every_bad_line() {
+        let tsv = "FF04C3DC-F0FE-472E-8737-0F4034C049F0\t2021-07-08\t1260\tY\tOpening Balance\tSam Hill Credit Union\tOpen Account\t500.00\t\r\n\
+                   1422CBC6-7B0B-4584-B7AB-35167CC5647B\tyesterday\t\tN\t\tFake Street Electronics\tHead set\t\t200.00\r\n\
+                   BB22187E-0BD3-41E8-B3D8-8136BD700865\t2021-07-08\r\n";
+
+        match Record::from_tsv_reader(tsv.as_bytes()) {
+            Err(Error::Rows(errors)) => {
+                assert_eq!(errors.len(), 2);
+                assert!(matches!(errors[0], Error::Field { line: Some(2), column: 2, .. }));
+                assert!(matches!(errors[1], Error::Field { line: Some(3), column: 3, .. }))
+            },
+            result => panic!("expected two bad rows, got {:?}", result)
+        }
+    }
+
+    #[test]
+    fn lenient_tsv_reader_skips_bad_lines() {
+        let tsv = "FF04C3DC-F0FE-472E-8737-0F4034C049F0\t2021-07-08\t1260\tY\tOpening Balance\tSam Hill Credit Union\tOpen Account\t500.00\t\r\n\
+                   1422CBC6-7B0B-4584-B7AB-35167CC5647B\t2021-07-08\tDEP\tN\t\tFake Street Electronics\tHead set\t\t200.00\r\n";
+
+        let (records, errors) = Record::from_tsv_reader_lenient(tsv.as_bytes()).unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(errors.len(), 1)
+    }
+
+    #[test]
+    fn write_tsv_with_header_and_read_it_back() {
+        let records: Vec<Record> = vec![
+            Record::from("FF04C3DC-F0FE-472E-8737-0F4034C049F0", Transaction::from(Some("2021-7-8"), Some(1260), Some("Opening Balance"), "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, true).unwrap())
+        ];
+
+        let mut buffer: Vec<u8> = vec![];
+        records.to_tsv_writer_with(&mut buffer, &Layout::default().with_header(true)).unwrap();
+
+        let tsv = String::from_utf8(buffer.clone()).unwrap();
+        assert!(tsv.starts_with("id\tdate\tcheck_number\treconciled\tcategory\tvendor\tmemo\tdeposit\twithdrawal\r\n"));
+
+        let loaded_records = Record::from_tsv_reader(buffer.as_slice()).unwrap();
+        assert_eq!(loaded_records[0].transaction, records[0].transaction)
+    }
+
+    #[test]
+    fn read_tsv_with_reordered_and_extra_columns_from_header() {
+        let tsv = "Date\tPayee\tWithdrawal\tDeposit\tBalance\r\n\
+                   2021-07-08\tSam Hill Credit Union\t\t500.00\t500.00\r\n\
+                   2021-07-08\tFake Street Electronics\t200.00\t\t300.00\r\n";
+
+        let records = Record::from_tsv_reader(tsv.as_bytes()).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].transaction, Transaction::from(Some("2021-7-8"), None, None, "Fake Street Electronics", "", 200.0, TransactionType::Withdrawal, false).unwrap())
+    }
+
+    #[test]
+    fn write_and_read_tsv_with_custom_layout() {
+        let layout = Layout::new(vec![Column::Date, Column::Vendor, Column::Ignored, Column::Deposit, Column::Withdrawal]);
+        let records: Vec<Record> = vec![
+            Record::from("1422CBC6-7B0B-4584-B7AB-35167CC5647B", Transaction::from(Some("2021-7-8"), None, None, "Fake Street Electronics", "", 200.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        let mut buffer: Vec<u8> = vec![];
+        records.to_tsv_writer_with(&mut buffer, &layout).unwrap();
+
+        assert_eq!(String::from_utf8(buffer.clone()).unwrap(), "2021-07-08\tFake Street Electronics\t\t\t200.00\r\n");
+        assert_eq!(Record::from_tsv_reader_with(buffer.as_slice(), &layout).unwrap()[0].transaction, records[0].transaction)
+    }
+
+    #[test]
+    fn write_and_read_csv_with_quoted_memo() {
+        let records: Vec<Record> = vec![
+            Record::from("FF04C3DC-F0FE-472E-8737-0F4034C049F0", Transaction::from(Some("2021-7-8"), Some(1260), None, "Sam Hill Credit Union, Inc.", "Open \"Savings\"\r\nand\tChecking", 500.0, TransactionType::Deposit, false).unwrap())
+        ];
+
+        let mut buffer: Vec<u8> = vec![];
+        CsvFormat::new().write(&records, &mut buffer).unwrap();
+
+        assert_eq!(String::from_utf8(buffer.clone()).unwrap(), "id,date,check_number,reconciled,category,vendor,memo,deposit,withdrawal\r\n\
+                                                                FF04C3DC-F0FE-472E-8737-0F4034C049F0,2021-07-08,1260,N,,\"Sam Hill Credit Union, Inc.\",\"Open \"\"Savings\"\"\r\nand\tChecking\",500.00,\r\n");
+
+        let loaded_records = Record::from_csv_reader(buffer.as_slice(), &CsvFormat::new()).unwrap();
+        assert_eq!(loaded_records[0].transaction, records[0].transaction)
+    }
+
+    #[test]
+    fn read_csv_with_signed_amounts_and_semicolons() {
+        let csv = "Date;Payee;Amount\n2021-07-08;Sam Hill Credit Union;500.00\n2021-07-08;\"Fake Street; Electronics\";-200.00\n";
+        let format = CsvFormat::new().with_delimiter(';').with_layout(Layout::default().with_signed_amount());
+
+        let records = Record::from_csv_reader(csv.as_bytes(), &format).unwrap();
+
+        assert_eq!(records[0].transaction.transaction_type, TransactionType::Deposit);
+        assert_eq!(records[1].transaction, Transaction::from(Some("2021-7-8"), None, None, "Fake Street; Electronics", "", 200.0, TransactionType::Withdrawal, false).unwrap())
+    }
+
+    #[test]
+    fn write_csv_with_signed_amounts() {
+        let records: Vec<Record> = vec![
+            Record::from("1422CBC6-7B0B-4584-B7AB-35167CC5647B", Transaction::from(Some("2021-7-8"), None, None, "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+        let format = CsvFormat::new().with_layout(Layout::new(vec![Column::Date, Column::Vendor, Column::Deposit, Column::Withdrawal]).with_signed_amount());
+
+        let mut buffer: Vec<u8> = vec![];
+        format.write(&records, &mut buffer).unwrap();
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), "2021-07-08,Fake Street Electronics,-200.00\r\n")
+    }
+
+    #[test]
+    fn csv_with_unclosed_quote_errors_out() {
+        let csv = "2021-07-08,\"Fake Street Electronics,-200.00\n";
+        let format = CsvFormat::new().with_layout(Layout::new(vec![Column::Date, Column::Vendor, Column::Amount]));
+
+        assert!(matches!(Record::from_csv_reader(csv.as_bytes(), &format), Err(Error::Field { line: Some(1), column: 2, .. })))
+    }
+
+    #[test]
+    fn load_records_from_sgml_ofx() {
+        let ofx = "OFXHEADER:100\r\nDATA:OFXSGML\r\nVERSION:102\r\n\r\n\
+                   <OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS><CURDEF>USD\r\n\
+                   <BANKACCTFROM><BANKID>123456789<ACCTID>0001234<ACCTTYPE>CHECKING</BANKACCTFROM>\r\n\
+                   <BANKTRANLIST><DTSTART>20210701<DTEND>20210731\r\n\
+                   <STMTTRN><TRNTYPE>CHECK<DTPOSTED>20210708120000[-5:EST]<TRNAMT>-200.00<FITID>2021070801<CHECKNUM>1261<NAME>Fake Street Electronics<MEMO>Head set</STMTTRN>\r\n\
+                   <STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20210709<TRNAMT>50000.00<FITID>2021070901<NAME>Velociraptor Entertainment &amp; Co</STMTTRN>\r\n\
+                   </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>";
+
+        let records = Record::from_ofx_reader(ofx.as_bytes()).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].transaction, Transaction::from(Some("2021-7-8"), Some(1261), None, "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap());
+        assert_eq!(records[1].transaction, Transaction::from(Some("2021-7-9"), None, None, "Velociraptor Entertainment & Co", "", 50000.0, TransactionType::Deposit, false).unwrap());
+        assert_eq!(Record::from_ofx_reader(ofx.as_bytes()).unwrap(), records)
+    }
+
+    #[test]
+    fn load_records_from_xml_ofx() {
+        let ofx = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <?OFX OFXHEADER="200" VERSION="220" SECURITY="NONE" OLDFILEUID="NONE" NEWFILEUID="NONE"?>
+        <OFX>
+            <CREDITCARDMSGSRSV1><CCSTMTTRNRS><CCSTMTRS>
+                <CCACCTFROM><ACCTID>4111</ACCTID></CCACCTFROM>
+                <BANKTRANLIST>
+                    <STMTTRN>
+                        <TRNTYPE>DEBIT</TRNTYPE>
+                        <DTPOSTED>20210708</DTPOSTED>
+                        <TRNAMT>-45.10</TRNAMT>
+                        <FITID>A1</FITID>
+                        <NAME>Corner Grocery</NAME>
+                    </STMTTRN>
+                </BANKTRANLIST>
+            </CCSTMTRS></CCSTMTTRNRS></CREDITCARDMSGSRSV1>
+        </OFX>"#;
+
+        let records = Record::from_ofx_reader(ofx.as_bytes()).unwrap();
+
+        assert_eq!(records[0].transaction, Transaction::from(Some("2021-7-8"), None, None, "Corner Grocery", "", 45.10, TransactionType::Withdrawal, false).unwrap())
+    }
+
+    #[test]
+    fn ofx_without_amount_errors_out() {
+        let ofx = "<OFX>\n<STMTTRN>\n<DTPOSTED>20210708\n<FITID>A1\n</STMTTRN>\n</OFX>";
+
+        assert!(matches!(Record::from_ofx_reader(ofx.as_bytes()), Err(Error::Entry { line: 2, source: None, .. })))
+    }
+
+    #[test]
+    fn load_records_from_qif() {
+        let qif = "!Type:Bank\r\n\
+                   D7/8'21\r\nT500.00\r\nN1260\r\nPSam Hill Credit Union\r\nMOpen Account\r\nLOpening Balance\r\nCX\r\n^\r\n\
+                   D7/ 8/2021\r\nT-1,200.00\r\nNATM\r\nPFake Street Electronics\r\n^\r\n";
+
+        let records = Record::from_qif_reader(qif.as_bytes()).unwrap();
+
+        assert_eq!(records[0].transaction, Transaction::from(Some("2021-7-8"), Some(1260), Some("Opening Balance"), "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, true).unwrap());
+        assert_eq!(records[1].transaction, Transaction::from(Some("2021-7-8"), None, None, "Fake Street Electronics", "", 1200.0, TransactionType::Withdrawal, false).unwrap())
+    }
+
+    #[test]
+    fn write_and_read_qif() {
+        let records: Vec<Record> = vec![
+            Record::from("FF04C3DC-F0FE-472E-8737-0F4034C049F0", Transaction::from(Some("2021-7-8"), Some(1260), Some("Opening Balance"), "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, true).unwrap()),
+            Record::from("1422CBC6-7B0B-4584-B7AB-35167CC5647B", Transaction::from(Some("2021-7-8"), None, Some("Gifts"), "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        let mut buffer: Vec<u8> = vec![];
+        Format::Qif.write(&records, &mut buffer).unwrap();
+
+        assert!(String::from_utf8(buffer.clone()).unwrap().starts_with("!Type:Bank\r\nD7/8/2021\r\nT500.00\r\nN1260\r\n"));
+
+        let loaded_records = Record::from_qif_reader(buffer.as_slice()).unwrap();
+
+        assert_eq!(loaded_records[0].transaction, records[0].transaction);
+        assert_eq!(loaded_records[1].transaction, records[1].transaction)
+    }
+
+    #[test]
+    fn qif_with_bad_date_reports_line() {
+        let qif = "!Type:Bank\nD13/40/2021\nT5.00\n^\n";
+
+        assert!(matches!(Record::from_qif_reader(qif.as_bytes()), Err(Error::Entry { line: 2, .. })))
+    }
+
+    #[test]
+    fn running_balance_in_date_order() {
+        let records: Vec<Record> = vec![
+            Record::from("1422CBC6-7B0B-4584-B7AB-35167CC5647B", Transaction::from(Some("2021-7-9"), None, None, "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("FF04C3DC-F0FE-472E-8737-0F4034C049F0", Transaction::from(Some("2021-7-8"), Some(1260), None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, true).unwrap()),
+            Record::from("BB22187E-0BD3-41E8-B3D8-8136BD700865", Transaction::from(Some("2021-7-10"), None, None, "Velociraptor Entertainment", "", 50000.0, TransactionType::Deposit, false).unwrap())
+        ];
+
+        let running_balance = RunningBalance::new(&records).with_opening_balance(Money::from_cents(10000));
+        let balances: Vec<(&str, Money)> = running_balance.iter().map(|(record, balance)| (record.id.as_str(), balance)).collect();
+
+        assert_eq!(balances, vec![
+            ("FF04C3DC-F0FE-472E-8737-0F4034C049F0", Money::from_cents(60000)),
+            ("1422CBC6-7B0B-4584-B7AB-35167CC5647B", Money::from_cents(40000)),
+            ("BB22187E-0BD3-41E8-B3D8-8136BD700865", Money::from_cents(5040000))
+        ]);
+        assert_eq!(running_balance.current(), Money::from_cents(5040000));
+        assert_eq!(running_balance.reconciled(), Money::from_cents(60000));
+        assert_eq!(running_balance.as_of(NaiveDate::from_ymd_opt(2021, 7, 9).unwrap()), Money::from_cents(40000))
+    }
+
+    #[test]
+    fn parse_money_exactly() {
+        assert_eq!("19.99".parse::<Money>(), Ok(Money::from_cents(1999)));
+        assert_eq!("-5".parse::<Money>(), Ok(Money::from_cents(-500)));
+        assert_eq!("1.500".parse::<Money>(), Ok(Money::from_cents(150)));
+        assert!(matches!("1.005".parse::<Money>(), Err(MoneyParseError::TooPrecise(_))));
+        assert!(matches!("abc".parse::<Money>(), Err(MoneyParseError::InvalidNumber(_))));
+        assert_eq!(Money::from_cents(-5).to_string(), "-0.05")
+    }
+
+    #[test]
+    fn adding_money_does_not_drift() {
+        let dime = Money::from_f64(0.1).unwrap();
+        let total: Money = std::iter::repeat_n(dime, 1000).sum();
+
+        assert_eq!(total, Money::from_cents(10000));
+        assert_eq!(total.to_string(), "100.00")
+    }
+
+    #[test]
+    fn money_round_trips_through_json() {
+        let transaction = Transaction::from(Some("2021-7-8"), None, None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap();
+        let json = serde_json::to_string(&transaction).unwrap();
+
+        assert!(json.contains("\"amount\":500.0"));
+        assert_eq!(serde_json::from_str::<Transaction>(&json).unwrap().amount, Money::from_cents(50000));
+        assert_eq!(serde_json::from_str::<Money>("0.295").unwrap(), Money::from_cents(30))
+    }
+
+    #[test]
+    fn register_keeps_records_in_date_order() {
+        let mut register = Register::new();
+
+        register.insert(Record::from("B", Transaction::from(Some("2021-7-9"), None, None, "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap())).unwrap();
+        register.insert(Record::from("A", Transaction::from(Some("2021-7-8"), None, None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap())).unwrap();
+        register.insert(Record::from("C", Transaction::from(Some("2021-7-9"), None, None, "Velociraptor Entertainment", "", 50.0, TransactionType::Deposit, false).unwrap())).unwrap();
+
+        let ids: Vec<&str> = register.iter().map(|record| record.id.as_str()).collect();
+
+        assert_eq!(ids, vec!["A", "B", "C"]);
+        assert_eq!(register.get("C").unwrap().transaction.vendor, "Velociraptor Entertainment")
+    }
+
+    #[test]
+    fn register_enforces_unique_ids() {
+        let record = Record::from("A", Transaction::from(Some("2021-7-8"), None, None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap());
+        let mut register = Register::try_from(vec![record.clone()]).unwrap();
+
+        assert!(matches!(register.insert(record.clone()), Err(Error::DuplicateId(id)) if id == "A"));
+        assert!(matches!(Register::try_from(vec![record.clone(), record.clone()]), Err(Error::DuplicateId(_))));
+        assert!(matches!(register.update(Record::from("B", Transaction::new())), Err(Error::UnknownId(_))));
+        assert!(register.upsert(Record::from("B", Transaction::new())).is_none());
+        assert_eq!(register.remove("A"), Some(record));
+        assert!(!register.contains("A"));
+        assert!(register.contains("B"))
+    }
+
+    #[test]
+    fn register_moves_updated_records() {
+        let mut register = Register::try_from(vec![
+            Record::from("A", Transaction::from(Some("2021-7-8"), None, None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-7-9"), None, None, "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap())
+        ]).unwrap();
+
+        let mut record = register.get("A").unwrap().clone();
+        record.transaction.date = "2021-7-10".local_datetime().unwrap();
+
+        let old_record = register.update(record).unwrap();
+
+        assert_eq!(old_record.transaction.date, "2021-7-8".local_datetime().unwrap());
+        assert_eq!(register.records()[1].id, "A");
+        assert_eq!(register.get("B").unwrap().id, "B");
+
+        let records: Vec<Record> = register.into();
+
+        assert_eq!(records.len(), 2)
+    }
+
+    #[test]
+    fn reconcile_against_statement() {
+        let mut register = Register::try_from(vec![
+            Record::from("A", Transaction::from(Some("2021-7-8"), None, None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, true).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-7-9"), Some(1260), None, "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("C", Transaction::from(Some("2021-7-12"), None, None, "Velociraptor Entertainment", "", 50.0, TransactionType::Deposit, false).unwrap()),
+            Record::from("D", Transaction::from(Some("2021-8-2"), None, None, "Sam Hill Credit Union", "Interest", 1.0, TransactionType::Deposit, false).unwrap())
+        ]).unwrap();
+
+        let mut reconciliation = Reconciliation::new(&register, NaiveDate::from_ymd_opt(2021, 7, 31).unwrap(), Money::from_cents(30000));
+        let candidates: Vec<&str> = reconciliation.candidates().iter().map(|record| record.id.as_str()).collect();
+
+        assert_eq!(candidates, vec!["B", "C"]);
+        assert_eq!(reconciliation.starting_balance(), Money::from_cents(50000));
+        assert!(matches!(reconciliation.clear("D"), Err(Error::UnknownId(_))));
+
+        assert!(reconciliation.toggle("B").unwrap());
+        assert_eq!(reconciliation.difference(), Money::ZERO);
+        assert!(reconciliation.toggle("C").unwrap());
+        assert_eq!(reconciliation.difference(), Money::from_cents(-5000));
+        assert!(matches!(reconciliation.complete(&mut register), Err(Error::Unbalanced(difference)) if difference == Money::from_cents(-5000)));
+        assert!(!register.get("B").unwrap().transaction.is_reconciled);
+
+        reconciliation.unclear("C");
+        reconciliation.complete(&mut register).unwrap();
+
+        assert!(register.get("B").unwrap().transaction.is_reconciled);
+        assert!(!register.get("C").unwrap().transaction.is_reconciled)
+    }
+
+    #[test]
+    fn reconciliation_fails_when_records_change() {
+        let record = Record::from("A", Transaction::from(Some("2021-7-8"), None, None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap());
+        let mut register = Register::try_from(vec![record.clone()]).unwrap();
+
+        let mut reconciliation = Reconciliation::new(&register, NaiveDate::from_ymd_opt(2021, 7, 31).unwrap(), Money::from_cents(50000));
+        reconciliation.clear_all();
+
+        let mut changed_record = record.clone();
+        changed_record.transaction.amount = Money::from_cents(40000);
+        register.update(changed_record).unwrap();
+
+        assert!(matches!(reconciliation.complete(&mut register), Err(Error::Changed(_))));
+
+        register.remove("A");
+
+        assert!(matches!(reconciliation.complete(&mut register), Err(Error::UnknownId(_))))
+    }
+
+    #[test]
+    fn explain_failed_reconciliation() {
+        let register = Register::try_from(vec![
+            Record::from("A", Transaction::from(Some("2021-7-8"), None, None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-7-9"), Some(1260), None, "Fake Street Electronics", "Head set", 21.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("C", Transaction::from(Some("2021-7-10"), Some(1260), None, "Velociraptor Entertainment", "", 12.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("D", Transaction::from(Some("2021-7-11"), None, None, "Sam Hill Credit Union", "Fee", 5.0, TransactionType::Withdrawal, false).unwrap())
+        ]).unwrap();
+
+        // the statement includes C, which was not ticked off.
+        let mut reconciliation = Reconciliation::new(&register, NaiveDate::from_ymd_opt(2021, 7, 31).unwrap(), Money::from_cents(46700));
+        reconciliation.clear("A").unwrap();
+        reconciliation.clear("B").unwrap();
+
+        let explanations = reconciliation.explain();
+
+        assert_eq!(explanations[0], Discrepancy::Uncleared(vec![String::from("C")]));
+        assert!(explanations.contains(&Discrepancy::DuplicateCheckNumber { check_number: 1260, ids: vec![String::from("B"), String::from("C")] }));
+
+        // the statement shows 12 for B, which was entered as 21.
+        let mut reconciliation = Reconciliation::new(&register, NaiveDate::from_ymd_opt(2021, 7, 31).unwrap(), Money::from_cents(47600));
+        reconciliation.clear("A").unwrap();
+        reconciliation.clear("B").unwrap();
+        reconciliation.clear("C").unwrap();
+
+        assert!(reconciliation.explain().contains(&Discrepancy::TransposedDigits { id: String::from("B"), amount: Money::from_cents(1200) }))
+    }
+
+    #[test]
+    fn find_sign_flips_and_groups() {
+        let records = vec![
+            Record::from("A", Transaction::from(Some("2021-7-8"), None, None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, true).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-7-9"), None, None, "Fake Street Electronics", "Refund", 30.0, TransactionType::Deposit, true).unwrap()),
+            Record::from("C", Transaction::from(Some("2021-7-10"), None, None, "Velociraptor Entertainment", "", 10.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("D", Transaction::from(Some("2021-7-11"), None, None, "Sam Hill Credit Union", "Fee", 50.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        let finder = DiscrepancyFinder::new(&records);
+
+        assert_eq!(finder.find(Money::from_cents(-6000)), vec![
+            Discrepancy::Uncleared(vec![String::from("C"), String::from("D")]),
+            Discrepancy::SignFlip { id: String::from("B") }
+        ]);
+        assert!(finder.with_max_subset_size(1).find(Money::from_cents(-6000)).iter().all(|discrepancy| !matches!(discrepancy, Discrepancy::Uncleared(_))))
+    }
+
+    #[test]
+    fn stop_searching_many_unmatched_records() {
+        let records: Vec<Record> = (0..60).map(|index| Record::from(&index.to_string(), Transaction::from(Some("2021-7-8"), None, None, "Fake Street Market", "", 1.0 + index as f64, TransactionType::Withdrawal, false).unwrap())).collect();
+
+        // no group can add up to a deposit, so without a limit every group of up to 60 records would be tried.
+        let discrepancies = DiscrepancyFinder::new(&records).with_max_subset_size(60).find(Money::from_cents(100));
+
+        assert_eq!(discrepancies, vec![Discrepancy::SearchLimitReached { groups: 1_000_000 }]);
+        assert_eq!(DiscrepancyFinder::new(&records).with_max_subset_size(60).with_max_groups(100).find(Money::from_cents(100)), vec![Discrepancy::SearchLimitReached { groups: 100 }]);
+        assert_eq!(DiscrepancyFinder::new(&records).find(Money::from_cents(-300))[0], Discrepancy::Uncleared(vec![String::from("2")]))
+    }
+
+    #[test]
+    fn report_by_category_and_month() {
+        let mut records = vec![
+            Record::from("A", Transaction::from(Some("2021-7-8"), None, Some("Income"), "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-7-9"), None, Some("Electronics"), "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("C", Transaction::from(Some("2021-7-20"), None, Some("Electronics"), "Fake Street Electronics", "Refund", 50.0, TransactionType::Deposit, false).unwrap()),
+            Record::from("D", Transaction::from(Some("2021-8-2"), None, None, "Sam Hill Credit Union", "Fee", 5.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        let report = Report::new(&records, Period::Month);
+        let rows: Vec<(&str, Option<&str>, Money, Money, Money)> = report.rows().iter().map(|row| (row.period.as_str(), row.category.as_deref(), row.deposits, row.withdrawals, row.net)).collect();
+
+        assert_eq!(rows, vec![
+            ("2021-07", Some("Electronics"), Money::from_cents(5000), Money::from_cents(20000), Money::from_cents(-15000)),
+            ("2021-07", Some("Income"), Money::from_cents(50000), Money::ZERO, Money::from_cents(50000)),
+            ("2021-08", None, Money::ZERO, Money::from_cents(500), Money::from_cents(-500))
+        ]);
+
+        records.push(Record::from("E", Transaction::from(Some("2021-10-1"), None, Some("Income"), "Sam Hill Credit Union", "Interest", 1.0, TransactionType::Deposit, false).unwrap()));
+
+        let report = Report::new(&records, Period::Quarter);
+
+        assert_eq!(report.rows().iter().map(|row| row.period.as_str()).collect::<Vec<&str>>(), vec!["2021-Q3", "2021-Q3", "2021-Q3", "2021-Q4"]);
+
+        let report = Report::new(&records, Period::Custom { start: NaiveDate::from_ymd_opt(2021, 8, 1).unwrap(), end: NaiveDate::from_ymd_opt(2021, 12, 31).unwrap() });
+
+        assert_eq!(report.rows().len(), 2)
+    }
+
+    #[test]
+    fn write_report_as_csv_json_and_text() {
+        let records = vec![
+            Record::from("A", Transaction::from(Some("2021-7-8"), None, Some("Income, Other"), "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-7-9"), None, None, "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        let report = Report::new(&records, Period::Year);
+
+        let mut csv: Vec<u8> = vec![];
+        report.to_csv_writer(&mut csv).unwrap();
+
+        assert_eq!(String::from_utf8(csv).unwrap(), "period,start,end,category,deposits,withdrawals,net\r\n2021,2021-01-01,2021-12-31,\"Income, Other\",500.00,0.00,500.00\r\n2021,2021-01-01,2021-12-31,Uncategorized,0.00,200.00,-200.00\r\n");
+
+        let mut json: Vec<u8> = vec![];
+        report.to_json_writer(&mut json).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+
+        assert_eq!(json[1]["category"], serde_json::Value::Null);
+        assert_eq!(json[1]["net"], serde_json::json!(-200.0));
+        assert!(report.to_string().lines().nth(2).unwrap().starts_with("2021    Uncategorized"))
+    }
+
+    #[test]
+    fn compare_budgets_with_spending() {
+        let records = vec![
+            Record::from("A", Transaction::from(Some("2021-6-10"), None, Some("Groceries"), "Grocery Outlet", "", 300.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-7-5"), None, Some("Groceries"), "Grocery Outlet", "", 450.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("C", Transaction::from(Some("2021-7-6"), None, Some("Dining"), "Pizza Place", "", 30.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("D", Transaction::from(Some("2021-7-20"), None, Some("Groceries"), "Grocery Outlet", "", 50.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        let budgets = vec![
+            Budget::new("Groceries", Money::from_cents(40000)),
+            Budget::new("Groceries", Money::from_cents(40000)).with_rollover(Rollover::Unspent),
+            Budget::new("Dining", Money::from_cents(6200)).with_rollover(Rollover::All).with_start(NaiveDate::from_ymd_opt(2021, 6, 1).unwrap())
+        ];
+
+        let report = BudgetReport::new(&budgets, &records, NaiveDate::from_ymd_opt(2021, 7, 15).unwrap());
+        let rows = report.rows();
+
+        assert_eq!(rows[0].period, "2021-07");
+        assert_eq!(rows[0].spent, Money::from_cents(45000));
+        assert_eq!(rows[0].remaining, Money::from_cents(-5000));
+        assert_eq!(rows[0].overspent, Money::from_cents(5000));
+        assert_eq!(rows[0].projected, Money::from_cents(93000));
+
+        assert_eq!(rows[1].carried_over, Money::from_cents(10000));
+        assert_eq!(rows[1].remaining, Money::from_cents(5000));
+        assert_eq!(rows[1].overspent, Money::ZERO);
+
+        assert_eq!(rows[2].carried_over, Money::from_cents(6200));
+        assert_eq!(rows[2].remaining, Money::from_cents(9400))
+    }
+
+    #[test]
+    fn rollover_starts_with_the_budget() {
+        let records = vec![
+            Record::from("A", Transaction::from(Some("2020-1-2"), None, Some("Income"), "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-6-10"), None, Some("Groceries"), "Grocery Outlet", "", 60.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        let budget = Budget::new("Groceries", Money::from_cents(10000)).with_rollover(Rollover::Unspent);
+        let date = NaiveDate::from_ymd_opt(2021, 7, 15).unwrap();
+
+        // the old record in another category does not add 17 months of unspent money.
+        assert_eq!(BudgetReport::new(std::slice::from_ref(&budget), &records, date).rows()[0].carried_over, Money::from_cents(4000));
+        assert_eq!(BudgetReport::new(&[budget.with_start(NaiveDate::from_ymd_opt(2021, 5, 20).unwrap())], &records, date).rows()[0].carried_over, Money::from_cents(14000));
+        assert_eq!(BudgetReport::new(&[Budget::new("Dining", Money::from_cents(6200)).with_rollover(Rollover::All)], &records, date).rows()[0].carried_over, Money::ZERO)
+    }
+
+    #[test]
+    fn save_and_load_budgets_next_to_bcheck_file() {
+        let bcheck_path = std::env::temp_dir().join("bcheck_budgets_test.bcheck");
+        let budgets = vec![Budget::new("Groceries", Money::from_cents(40000)).with_period(Period::Quarter).with_rollover(Rollover::All).with_start(NaiveDate::from_ymd_opt(2021, 7, 1).unwrap())];
+
+        let _ = std::fs::remove_file(Budget::companion_path(&bcheck_path));
+
+        assert!(Budget::from_companion_file(&bcheck_path).unwrap().is_empty());
+
+        Budget::save_companion_file(&budgets, &bcheck_path).unwrap();
+
+        assert_eq!(Budget::companion_path(&bcheck_path).file_name().unwrap(), "bcheck_budgets_test.budgets.json");
+        assert_eq!(Budget::from_companion_file(&bcheck_path).unwrap(), budgets)
+    }
+
+    #[test]
+    fn schedule_occurrences() {
+        let date = |year: i32, month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let rent = Transaction::from(None, None, Some("Housing"), "Landlord", "Rent", 950.0, TransactionType::Withdrawal, false).unwrap();
+
+        let monthly = Schedule::new(rent.clone(), date(2021, 1, 15), Frequency::MonthlyOnDay { day: 31 });
+        assert_eq!(monthly.occurrences().take(3).collect::<Vec<NaiveDate>>(), vec![date(2021, 1, 31), date(2021, 2, 28), date(2021, 3, 31)]);
+
+        let monthly = Schedule::new(rent.clone(), date(2021, 1, 15), Frequency::MonthlyOnDay { day: 1 }).with_end(ScheduleEnd::On(date(2021, 4, 1)));
+        assert_eq!(monthly.occurrences().collect::<Vec<NaiveDate>>(), vec![date(2021, 2, 1), date(2021, 3, 1), date(2021, 4, 1)]);
+
+        let payroll = Schedule::new(rent.clone(), date(2021, 7, 1), Frequency::LastBusinessDay);
+        assert_eq!(payroll.occurrences_between(date(2021, 7, 1), date(2021, 10, 31)), vec![date(2021, 7, 30), date(2021, 8, 31), date(2021, 9, 30), date(2021, 10, 29)]);
+
+        let biweekly = Schedule::new(rent.clone(), date(2021, 7, 2), Frequency::Biweekly).with_end(ScheduleEnd::After(3));
+        assert_eq!(biweekly.occurrences().collect::<Vec<NaiveDate>>(), vec![date(2021, 7, 2), date(2021, 7, 16), date(2021, 7, 30)]);
+
+        let yearly = Schedule::new(rent, date(2020, 2, 29), Frequency::Yearly);
+        assert_eq!(yearly.occurrences().nth(1), Some(date(2021, 2, 28)));
+        assert_eq!(yearly.occurrences().nth(4), Some(date(2024, 2, 29)))
+    }
+
+    #[test]
+    fn post_scheduled_transactions_once() {
+        let rent = Transaction::from(None, None, Some("Housing"), "Landlord", "Rent", 950.0, TransactionType::Withdrawal, false).unwrap();
+        let mut schedule = Schedule::new(rent, NaiveDate::from_ymd_opt(2021, 7, 1).unwrap(), Frequency::Weekly);
+
+        let records = schedule.post_through(NaiveDate::from_ymd_opt(2021, 7, 14).unwrap());
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].transaction.date, "2021-7-8".local_datetime().unwrap());
+        assert_eq!(records[0], schedule.record(NaiveDate::from_ymd_opt(2021, 7, 1).unwrap()));
+
+        let records = schedule.post_through(NaiveDate::from_ymd_opt(2021, 7, 22).unwrap());
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].transaction.date, "2021-7-15".local_datetime().unwrap());
+        assert_eq!(schedule.posted.len(), 4);
+
+        let bcheck_path = std::env::temp_dir().join("bcheck_schedules_test.bcheck");
+        Schedule::save_companion_file(&[schedule.clone()], &bcheck_path).unwrap();
+
+        assert_eq!(Schedule::from_companion_file(&bcheck_path).unwrap(), vec![schedule])
+    }
+
+    #[test]
+    fn schedules_to_and_from_rrule() {
+        let date = |year: i32, month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let schedules = vec![
+            (Frequency::Weekly, ScheduleEnd::Never, date(2021, 7, 2), "FREQ=WEEKLY"),
+            (Frequency::Biweekly, ScheduleEnd::After(10), date(2021, 7, 2), "FREQ=WEEKLY;INTERVAL=2;COUNT=10"),
+            (Frequency::MonthlyOnDay { day: 1 }, ScheduleEnd::On(date(2022, 6, 1)), date(2021, 7, 1), "FREQ=MONTHLY;BYMONTHDAY=1;UNTIL=20220601"),
+            (Frequency::MonthlyOnDay { day: 30 }, ScheduleEnd::Never, date(2021, 7, 30), "FREQ=MONTHLY;BYMONTHDAY=28,29,30;BYSETPOS=-1"),
+            (Frequency::LastBusinessDay, ScheduleEnd::Never, date(2021, 7, 1), "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1"),
+            (Frequency::Yearly, ScheduleEnd::Never, date(2021, 7, 1), "FREQ=YEARLY"),
+            (Frequency::Yearly, ScheduleEnd::Never, date(2020, 2, 29), "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=28,29;BYSETPOS=-1")
+        ];
+
+        for (frequency, end, start, rrule) in schedules {
+            let schedule = Schedule::new(Transaction::new(), start, frequency).with_end(end);
+
+            assert_eq!(schedule.rrule(), rrule);
+
+            let parsed_schedule = Schedule::from_rrule(Transaction::new(), start, &format!("RRULE:{}", rrule)).unwrap();
+
+            assert_eq!((parsed_schedule.frequency, parsed_schedule.end), (frequency, end));
+        }
+
+        assert_eq!(Schedule::from_rrule(Transaction::new(), date(2021, 7, 15), "FREQ=MONTHLY").unwrap().frequency, Frequency::MonthlyOnDay { day: 15 });
+        assert!(matches!(Schedule::from_rrule(Transaction::new(), date(2021, 7, 1), "FREQ=DAILY"), Err(Error::Invalid { .. })));
+        assert!(matches!(Schedule::from_rrule(Transaction::new(), date(2021, 7, 1), "FREQ=WEEKLY;BYDAY=MO"), Err(Error::Invalid { .. })))
+    }
+
+    // expand an RRULE the way RFC 5545 does, for the parts that schedules write, counting DTSTART as the first occurrence.
+    fn expand_rrule(rrule: &str, dtstart: NaiveDate, through: NaiveDate) -> Vec<NaiveDate> {
+        use chrono::{ Datelike, Days, Months };
+
+        let parts: std::collections::HashMap<&str, &str> = rrule.split(';').filter_map(|part| part.split_once('=')).collect();
+        let interval: u64 = parts.get("INTERVAL").map_or(1, |interval| interval.parse().unwrap());
+        let month_days: Option<Vec<u32>> = parts.get("BYMONTHDAY").map(|days| days.split(',').map(|day| day.parse().unwrap()).collect());
+        let month: u32 = parts.get("BYMONTH").map_or(dtstart.month(), |month| month.parse().unwrap());
+        let is_last = parts.get("BYSETPOS") == Some(&"-1");
+        let mut dates = vec![dtstart];
+
+        for index in 0.. {
+            let (first, last) = match parts["FREQ"] {
+                "WEEKLY" => {
+                    let date = dtstart + Days::new(7 * interval * index);
+
+                    (date, date)
+                },
+                "MONTHLY" => {
+                    let first = dtstart.with_day(1).unwrap() + Months::new(index as u32);
+
+                    (first, first + Months::new(1) - Days::new(1))
+                },
+                _ => (NaiveDate::from_ymd_opt(dtstart.year() + index as i32, 1, 1).unwrap(), NaiveDate::from_ymd_opt(dtstart.year() + index as i32, 12, 31).unwrap())
+            };
+
+            if first > through {
+                break
+            }
+
+            let candidates: Vec<NaiveDate> = first.iter_days().take_while(|date| *date <= last).filter(|date| {
+                (parts["FREQ"] != "YEARLY" || date.month() == month) && match (&month_days, parts.get("BYDAY")) {
+                    (Some(days), _) => days.contains(&date.day()),
+                    (None, Some(&"MO,TU,WE,TH,FR")) => date.weekday().number_from_monday() <= 5,
+                    _ => parts["FREQ"] == "WEEKLY" || date.day() == dtstart.day()
+                }
+            }).collect();
+
+            let candidates = if is_last { candidates.last().into_iter().copied().collect() } else { candidates };
+
+            dates.extend(candidates.into_iter().filter(|date| *date > dtstart));
+        }
+
+        let until = parts.get("UNTIL").map_or(NaiveDate::MAX, |until| NaiveDate::parse_from_str(until, "%Y%m%d").unwrap());
+        let count = parts.get("COUNT").map_or(usize::MAX, |count| count.parse().unwrap());
+
+        dates.into_iter().take_while(|date| *date <= until.min(through)).take(count).collect()
+    }
+
+    #[test]
+    fn rrules_expand_to_the_same_occurrences() {
+        let date = |year: i32, month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let through = date(2024, 12, 31);
+        let schedules = vec![
+            (Frequency::MonthlyOnDay { day: 15 }, ScheduleEnd::After(3), date(2021, 7, 3)),
+            (Frequency::MonthlyOnDay { day: 1 }, ScheduleEnd::On(date(2022, 6, 1)), date(2021, 7, 2)),
+            (Frequency::MonthlyOnDay { day: 31 }, ScheduleEnd::Never, date(2021, 2, 3)),
+            (Frequency::MonthlyOnDay { day: 30 }, ScheduleEnd::After(5), date(2021, 7, 31)),
+            (Frequency::LastBusinessDay, ScheduleEnd::On(date(2022, 3, 1)), date(2021, 7, 3)),
+            (Frequency::Weekly, ScheduleEnd::Never, date(2021, 7, 2)),
+            (Frequency::Biweekly, ScheduleEnd::After(10), date(2021, 7, 2)),
+            (Frequency::Yearly, ScheduleEnd::Never, date(2021, 7, 1)),
+            (Frequency::Yearly, ScheduleEnd::After(4), date(2020, 2, 29))
+        ];
+
+        for (frequency, end, start) in schedules {
+            let schedule = Schedule::new(Transaction::new(), start, frequency).with_end(end);
+            let occurrences: Vec<NaiveDate> = schedule.occurrences().take_while(|date| *date <= through).collect();
+
+            assert_eq!(schedule.first_occurrence(), occurrences.first().copied());
+            assert_eq!(expand_rrule(&schedule.rrule(), schedule.first_occurrence().unwrap(), through), occurrences, "{}", schedule.rrule());
+        }
+
+        // the first occurrence of a monthly schedule on the 15th that starts on the 3rd is the 15th, not the 3rd.
+        assert_eq!(Schedule::new(Transaction::new(), date(2021, 7, 3), Frequency::MonthlyOnDay { day: 15 }).first_occurrence(), Some(date(2021, 7, 15)))
+    }
+
+    #[test]
+    fn write_upcoming_transactions_as_ics() {
+        let rent = Transaction::from(None, None, Some("Housing, Rent"), "Landlord", "Unit 4", 950.0, TransactionType::Withdrawal, false).unwrap();
+        let schedule = Schedule::new(rent, NaiveDate::from_ymd_opt(2021, 7, 1).unwrap(), Frequency::MonthlyOnDay { day: 1 });
+        let records = schedule.records_between(NaiveDate::from_ymd_opt(2021, 7, 1).unwrap(), NaiveDate::from_ymd_opt(2021, 8, 31).unwrap());
+
+        let mut ics: Vec<u8> = vec![];
+        Format::Ics.write(&records, &mut ics).unwrap();
+        let ics = String::from_utf8(ics).unwrap();
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("DTSTART;VALUE=DATE:20210801\r\n"));
+        assert!(ics.contains("SUMMARY:Landlord -950.00\r\n"));
+        assert!(ics.contains("CATEGORIES:Housing\\, Rent\r\n"));
+        assert!(ics.contains(&format!("UID:{}@bcheck\r\n", records[0].id)));
+        assert!(ics.lines().all(|line| line.len() <= 75))
+    }
+
+    #[test]
+    fn forecast_balance_with_schedules() {
+        let date = |year: i32, month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        let rent = Transaction::from(None, None, Some("Housing"), "Landlord", "Rent", 950.0, TransactionType::Withdrawal, false).unwrap();
+        let pay = Transaction::from(None, None, Some("Income"), "Employer", "Pay", 600.0, TransactionType::Deposit, false).unwrap();
+
+        let mut rent_schedule = Schedule::new(rent, date(2021, 7, 1), Frequency::MonthlyOnDay { day: 1 });
+        let pay_schedule = Schedule::new(pay, date(2021, 7, 9), Frequency::Biweekly);
+
+        let mut records = vec![
+            Record::from("A", Transaction::from(Some("2021-6-30"), None, None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-7-20"), None, None, "Fake Street Electronics", "Head set", 100.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        // rent for July was already added to the register, so it should only be counted once.
+        records.extend(rent_schedule.post_through(date(2021, 7, 1)));
+
+        let schedules = vec![rent_schedule, pay_schedule];
+        let forecast = Forecast::new(&records, date(2021, 7, 1), date(2021, 8, 31)).with_schedules(&schedules).with_opening_balance(Money::from_cents(100000));
+        let days = forecast.days();
+
+        assert_eq!(days.len(), 62);
+        assert_eq!(days[0].balance, Money::from_cents(55000));
+        assert_eq!(days[0].records.len(), 1);
+        assert_eq!(days[8].balance, Money::from_cents(115000));
+
+        let lowest = forecast.lowest().unwrap();
+
+        assert_eq!((lowest.date, lowest.balance), (date(2021, 7, 1), Money::from_cents(55000)));
+        assert!(forecast.first_negative().is_none());
+
+        let forecast = forecast.with_opening_balance(Money::ZERO);
+
+        assert_eq!(forecast.first_negative().unwrap().date, date(2021, 7, 1));
+        assert_eq!(forecast.lowest().unwrap().balance, Money::from_cents(-45000))
+    }
+
+    #[test]
+    fn save_and_load_workbook() {
+        let path = std::env::temp_dir().join("bcheck_workbook_test.json");
+        let mut workbook = Workbook::new();
+
+        let checking = Account::new("Checking").with_institution("Sam Hill Credit Union").with_number("0012-3456-7890").with_opening_balance(Money::from_cents(10000)).with_register(Register::try_from(vec![
+            Record::from("A", Transaction::from(Some("2021-7-8"), None, None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap())
+        ]).unwrap());
+
+        workbook.add_account(checking).unwrap();
+        workbook.add_account(Account::new("Visa").with_type(AccountType::CreditCard).with_currency("cad")).unwrap();
+
+        assert!(matches!(workbook.add_account(Account::new("visa")), Err(Error::DuplicateAccount(_))));
+
+        workbook.save(&path).unwrap();
+
+        let loaded_workbook = Workbook::from_file(&path).unwrap();
+        let checking = loaded_workbook.account_named("checking").unwrap();
+        let visa = loaded_workbook.account_named("Visa").unwrap();
+
+        assert_eq!(checking.number_mask.as_deref(), Some("****7890"));
+        assert_eq!(checking.balance(), Money::from_cents(60000));
+        assert_eq!((visa.account_type, visa.currency.as_str()), (AccountType::CreditCard, "CAD"));
+        assert_eq!(loaded_workbook.account(&visa.id).unwrap().name, "Visa");
+        assert_eq!("credit card".parse::<AccountType>().unwrap(), AccountType::CreditCard)
+    }
+
+    #[test]
+    fn export_account_as_bcheck_file() {
+        let path = std::env::temp_dir().join("bcheck_workbook_export_test.bcheck");
+        let records = vec![Record::from("A", Transaction::from(Some("2021-7-8"), None, None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap())];
+
+        let mut workbook = Workbook::new();
+        workbook.add_account(Account::new("Checking").with_register(Register::try_from(records.clone()).unwrap())).unwrap();
+        workbook.account_named("Checking").unwrap().register.save(&path).unwrap();
+
+        assert_eq!(Record::from_file(&path).unwrap(), records);
+        assert_eq!(Account::from_bcheck_file("Imported", &path).unwrap().register.len(), 1)
+    }
+
+    #[test]
+    fn transfers_stay_in_sync() {
+        let mut workbook = Workbook::new();
+        let checking = Account::new("Checking");
+        let savings = Account::new("Savings").with_type(AccountType::Savings);
+        let (checking_id, savings_id) = (checking.id.clone(), savings.id.clone());
+
+        workbook.add_account(checking).unwrap();
+        workbook.add_account(savings).unwrap();
+
+        let transfer_id = workbook.transfer(&checking_id, &savings_id, NaiveDate::from_ymd_opt(2021, 7, 8).unwrap(), Money::from_cents(10000), "Rainy day fund").unwrap();
+
+        assert!(matches!(workbook.transfer(&checking_id, "missing", NaiveDate::from_ymd_opt(2021, 7, 8).unwrap(), Money::from_cents(10000), ""), Err(Error::UnknownAccount(_))));
+        assert_eq!(workbook.account(&checking_id).unwrap().balance(), Money::from_cents(-10000));
+        assert_eq!(workbook.account(&savings_id).unwrap().balance(), Money::from_cents(10000));
+        assert_eq!(workbook.account(&savings_id).unwrap().register[0].transaction.vendor, "Transfer from Checking");
+
+        let mut withdrawal = workbook.account(&checking_id).unwrap().register[0].clone();
+        withdrawal.transaction.amount = Money::from_cents(15000);
+        withdrawal.transaction.date = "2021-7-9".local_datetime().unwrap();
+        workbook.update_record(&checking_id, withdrawal).unwrap();
+
+        let deposit = &workbook.account(&savings_id).unwrap().register[0];
+
+        assert_eq!(deposit.transaction.amount, Money::from_cents(15000));
+        assert_eq!(deposit.transaction.date, "2021-7-9".local_datetime().unwrap());
+        assert_eq!(deposit.transfer_id.as_deref(), Some(transfer_id.as_str()));
+        assert!(workbook.validate_transfers().is_empty());
+        assert_eq!(workbook.remove_transfer(&transfer_id), 2)
+    }
+
+    #[test]
+    fn validate_broken_transfers() {
+        let mut workbook = Workbook::new();
+        let checking = Account::new("Checking");
+        let savings = Account::new("Savings");
+        let (checking_id, savings_id) = (checking.id.clone(), savings.id.clone());
+
+        workbook.add_account(checking).unwrap();
+        workbook.add_account(savings).unwrap();
+
+        let orphaned_id = workbook.transfer(&checking_id, &savings_id, NaiveDate::from_ymd_opt(2021, 7, 8).unwrap(), Money::from_cents(10000), "").unwrap();
+        let mismatched_id = workbook.transfer(&checking_id, &savings_id, NaiveDate::from_ymd_opt(2021, 7, 9).unwrap(), Money::from_cents(2000), "").unwrap();
+
+        let deposit_id = workbook.account(&savings_id).unwrap().register.iter().find(|record| record.transfer_id.as_ref() == Some(&orphaned_id)).unwrap().id.clone();
+        workbook.account_mut(&savings_id).unwrap().register.remove(&deposit_id);
+
+        // editing the register directly does not keep the other half in sync.
+        let account = workbook.account_mut(&savings_id).unwrap();
+        let mut deposit = account.register.iter().find(|record| record.transfer_id.as_ref() == Some(&mismatched_id)).unwrap().clone();
+        deposit.transaction.amount = Money::from_cents(2500);
+        account.register.update(deposit).unwrap();
+
+        let issues = workbook.validate_transfers();
+
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().any(|issue| matches!(issue, TransferIssue::Orphaned { transfer_id, account_id, .. } if *transfer_id == orphaned_id && *account_id == checking_id)));
+        assert!(issues.iter().any(|issue| matches!(issue, TransferIssue::Mismatched { transfer_id, records } if *transfer_id == mismatched_id && records.len() == 2)))
+    }
+
+    #[test]
+    fn splits_must_add_up() {
+        let mut transaction = Transaction::from(Some("2021-7-10"), Some(1261), Some("Groceries"), "Fake Street Market", "", 100.0, TransactionType::Withdrawal, false).unwrap();
+
+        let result = transaction.set_splits(vec![Split::new(Some("Groceries"), Money::from_cents(6000)), Split::new(Some("Household"), Money::from_cents(3000))]);
+
+        assert!(matches!(result, Err(Error::Splits { total: Some(total), .. }) if total == Money::from_cents(9000)));
+        assert!(transaction.splits.is_empty());
+
+        // records without splits are written the same way as before.
+        let record = Record::from("A", transaction.clone());
+        assert!(!serde_json::to_string(&record).unwrap().contains("splits"));
+
+        transaction.set_splits(vec![
+            Split::new(Some("Groceries"), Money::from_cents(6000)),
+            Split::new(Some("Household"), Money::from_cents(2500)).with_memo("Paper towels"),
+            Split::new(Some("Pharmacy"), Money::from_cents(1500))
+        ]).unwrap();
+
+        let json = serde_json::to_string(&vec![Record::from("A", transaction.clone())]).unwrap();
+        let decoded = Record::from_reader(json.as_bytes()).unwrap();
+
+        assert_eq!(decoded[0].transaction, transaction);
+        assert_eq!(decoded[0].transaction.category.as_deref(), Some("Groceries"));
+
+        let mut qif = vec![];
+        Format::Qif.write(&decoded, &mut qif).unwrap();
+
+        assert_eq!(Record::from_qif_reader(qif.as_slice()).unwrap()[0].transaction.splits, transaction.splits);
+
+        transaction.amount = Money::from_cents(9000);
+        let json = serde_json::to_string(&vec![Record::from("A", transaction)]).unwrap();
+
+        assert!(matches!(Record::from_reader(json.as_bytes()), Err(Error::Splits { .. })))
+    }
+
+    #[test]
+    fn reports_attribute_splits() {
+        let mut transaction = Transaction::from(Some("2021-7-10"), None, Some("Groceries"), "Fake Street Market", "", 100.0, TransactionType::Withdrawal, false).unwrap();
+
+        transaction.set_splits(vec![
+            Split::new(Some("Groceries"), Money::from_cents(6000)),
+            Split::new(Some("Household"), Money::from_cents(2500)),
+            Split::new(None, Money::from_cents(1500))
+        ]).unwrap();
+
+        let records = vec![
+            Record::from("A", transaction),
+            Record::from("B", Transaction::from(Some("2021-7-12"), None, Some("Groceries"), "Fake Street Market", "", 20.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        let report = Report::new(&records, Period::Month);
+        let rows: Vec<(Option<&str>, Money)> = report.rows().iter().map(|row| (row.category.as_deref(), row.withdrawals)).collect();
+
+        assert_eq!(rows, vec![
+            (Some("Groceries"), Money::from_cents(8000)),
+            (Some("Household"), Money::from_cents(2500)),
+            (None, Money::from_cents(1500))
+        ]);
+
+        let budgets = vec![Budget::new("Household", Money::from_cents(5000))];
+        let report = BudgetReport::new(&budgets, &records, NaiveDate::from_ymd_opt(2021, 7, 31).unwrap());
+
+        assert_eq!(report.rows()[0].spent, Money::from_cents(2500))
+    }
+
+    #[test]
+    fn filter_records() {
+        let mut split_transaction = Transaction::from(Some("2021-7-15"), None, Some("Groceries"), "Fake Street Market", "", 30.0, TransactionType::Withdrawal, false).unwrap();
+        split_transaction.set_splits(vec![Split::new(Some("Groceries"), Money::from_cents(2000)), Split::new(Some("Pharmacy"), Money::from_cents(1000))]).unwrap();
+
+        let records = vec![
+            Record::from("A", Transaction::from(Some("2021-7-8"), None, Some("Income"), "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, true).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-7-9"), Some(1260), Some("Electronics"), "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("C", split_transaction),
+            Record::from("D", Transaction::from(Some("2021-8-2"), None, None, "Sam Hill Credit Union", "Fee", 5.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        let ids = |filter: Filter| filter.apply(&records).iter().map(|record| record.id.as_str()).collect::<Vec<&str>>();
+
+        assert_eq!(ids(Filter::new()).len(), 4);
+        assert_eq!(ids(Filter::new().with_from(NaiveDate::from_ymd_opt(2021, 7, 9).unwrap()).with_to(NaiveDate::from_ymd_opt(2021, 7, 31).unwrap())), vec!["B", "C"]);
+        assert_eq!(ids(Filter::new().with_vendor("sam hill").with_type(TransactionType::Withdrawal)), vec!["D"]);
+        assert_eq!(ids(Filter::new().with_category("pharmacy")), vec!["C"]);
+        assert_eq!(ids(Filter::new().with_reconciled(false).with_amount_range(Some(Money::from_cents(1000)), Some(Money::from_cents(20000)))), vec!["B", "C"])
+    }
+
+    #[test]
+    fn detect_formats() {
+        assert_eq!(Format::from_path("/Users/bob/Documents/example.BCHECK"), Some(Format::Bcheck));
+        assert_eq!(Format::from_path("statement.qfx"), Some(Format::Ofx));
+        assert_eq!(Format::from_path("statement.dat"), None);
+        assert_eq!(Format::sniff(b"\xef\xbb\xbf  [{\"id\": \"A\"}]"), Some(Format::Bcheck));
+        assert_eq!(Format::sniff(b"!Type:Bank\r\nD7/8/2021\r\n"), Some(Format::Qif));
+        assert_eq!(Format::sniff(b"OFXHEADER:100\r\nDATA:OFXSGML\r\n"), Some(Format::Ofx));
+        assert_eq!(Format::sniff(b"id\tdate\tvendor\r\n"), Some(Format::Tsv));
+        assert_eq!(Format::sniff(b"date,vendor,amount\r\n"), Some(Format::Csv));
+        assert_eq!(Format::detect("statement.dat", b"!Type:CCard\n"), Some(Format::Qif));
+        assert_eq!("QFX".parse::<Format>().unwrap(), Format::Ofx);
+        assert!(!Format::Ofx.can_write() && !Format::Ics.can_read())
+    }
+
+    #[test]
+    fn convert_and_report_losses() {
+        let mut split_transaction = Transaction::from(Some("2021-7-10"), Some(1261), Some("Groceries"), "Fake Street Market", "", 30.0, TransactionType::Withdrawal, false).unwrap();
+        split_transaction.set_splits(vec![Split::new(Some("Groceries"), Money::from_cents(2000)), Split::new(Some("Pharmacy"), Money::from_cents(1000))]).unwrap();
+
+        let records = vec![
+            Record::from("A", Transaction::from(Some("2021-7-8"), None, Some("Income"), "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, true).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-7-9"), None, None, "Fake Street Electronics", "Head set\nand cable", 200.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("C", split_transaction)
+        ];
+
+        let path = std::env::temp_dir().join("convert_and_report_losses.bcheck");
+        records.save(&path).unwrap();
+
+        let conversion = Conversion::from_file(&path, None, Format::Tsv).unwrap();
+
+        assert_eq!(conversion.from_format(), Some(Format::Bcheck));
+        assert_eq!(conversion.losses(), &[(String::from("B"), vec![LostField::Memo]), (String::from("C"), vec![LostField::Splits])]);
+        assert!(Conversion::new(records.clone(), Format::Bcheck).is_lossless());
+        assert!(matches!(Conversion::from_file(&path, None, Format::Ofx), Err(Error::Invalid { .. })));
+
+        // without a memo spanning lines, records survive going to TSV and back.
+        let records: Vec<Record> = records.into_iter().filter(|record| record.id != "B").collect();
+        let mut tsv: Vec<u8> = vec![];
+        Conversion::new(records.clone(), Format::Tsv).to_writer(&mut tsv).unwrap();
+
+        let conversion = Conversion::from_reader(tsv.as_slice(), None, Format::Bcheck).unwrap();
+
+        assert_eq!(conversion.from_format(), Some(Format::Tsv));
+        assert_eq!(conversion.records()[0], records[0]);
+        assert_eq!(conversion.records()[1].transaction.splits, vec![])
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn store_records_in_sqlite() {
+        let mut split_transaction = Transaction::from(Some("2021-7-8"), Some(1261), Some("Groceries"), "Fake Street Market", "", 30.0, TransactionType::Withdrawal, false).unwrap();
+        split_transaction.set_splits(vec![Split::new(Some("Groceries"), Money::from_cents(2000)), Split::new(Some("Pharmacy"), Money::from_cents(1000))]).unwrap();
+
+        let mut records = vec![
+            Record::from("A", Transaction::from(Some("2021-7-8"), None, Some("Income"), "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, true).unwrap()),
+            Record::from("B", split_transaction),
+            Record::from("C", Transaction::from(Some("2021-7-9"), None, None, "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+        records[2].transfer_id = Some(String::from("D"));
+
+        let mut store = crate::SqliteStore::open_in_memory().unwrap();
+        store.save(&records).unwrap();
+
+        assert_eq!(store.records().unwrap(), records);
+        assert_eq!(store.current_balance().unwrap(), Money::from_cents(27000));
+        assert_eq!(store.reconciled_balance().unwrap(), Money::from_cents(50000));
+        assert_eq!(store.query(&Filter::new().with_category("pharmacy")).unwrap(), vec![records[1].clone()]);
+        assert_eq!(store.query(&Filter::new().with_category("Income").with_vendor("hill")).unwrap(), vec![records[0].clone()]);
+        assert!(store.query(&Filter::new().with_category("Groceries").with_reconciled(true)).unwrap().is_empty());
+
+        // moving a record to another day puts it after the records already on that day.
+        let mut moved_record = records.remove(0);
+        moved_record.transaction.date = "2021-7-9".local_datetime().unwrap();
+        store.update(moved_record.clone()).unwrap();
+        records.push(moved_record);
+
+        assert_eq!(store.load().unwrap().records(), records.as_slice());
+        assert!(matches!(store.insert(records[0].clone()), Err(Error::DuplicateId(_))));
+        assert!(matches!(store.update(Record::new()), Err(Error::UnknownId(_))))
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_changes_are_transactional() {
+        let records = vec![
+            Record::from("A", Transaction::from(Some("2021-7-8"), None, Some("Income"), "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, true).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-7-9"), None, None, "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        let path = std::env::temp_dir().join("sqlite_changes_are_transactional.tsv");
+        records.save_tsv(&path).unwrap();
+
+        let mut store = crate::SqliteStore::open_in_memory().unwrap();
+
+        assert_eq!(store.import_file(&path).unwrap(), 2);
+        assert!(matches!(store.import_file(&path), Err(Error::DuplicateId(_))));
+        assert_eq!(store.len().unwrap(), 2);
+
+        let result: Result<(), Error> = store.transaction(|store| {
+            store.remove("A")?;
+            store.insert(Record::new())?;
+            store.insert(records[1].clone())
+        });
+
+        assert!(matches!(result, Err(Error::DuplicateId(_))));
+        assert!(store.contains("A").unwrap());
+        assert_eq!(store.len().unwrap(), 2);
+
+        let path = std::env::temp_dir().join("sqlite_changes_are_transactional.bcheck");
+        store.export_file(&path).unwrap();
+
+        assert_eq!(Record::from_file(&path).unwrap(), store.records().unwrap())
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_vendors_match_the_same_as_filters() {
+        let mut records = vec![
+            Record::from("A", Transaction::from(Some("2021-7-8"), None, None, "Café Über", "", 4.5, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-7-8"), None, None, "CAFÉ ÜBER", "", 3.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("C", Transaction::from(Some("2021-7-9"), None, None, "Cafe Uber", "", 5.0, TransactionType::Withdrawal, false).unwrap()),
+            Record::from("D", Transaction::from(Some("2021-7-9"), None, None, "The \"Fake Street\" Market", "", 20.0, TransactionType::Withdrawal, false).unwrap())
+        ];
+
+        let mut store = crate::SqliteStore::open_in_memory().unwrap();
+        store.save(&records).unwrap();
+
+        records[2].transaction.vendor = String::from("Ücafé");
+        store.update(records[2].clone()).unwrap();
+        store.remove("B").unwrap();
+        records.remove(1);
+
+        for vendor in ["café", "CAFÉ", "Ü", "ü", "ber", "\"fake", "street\" m", "uber", ""] {
+            let filter = Filter::new().with_vendor(vendor);
+            let expected: Vec<Record> = filter.apply(&records).into_iter().cloned().collect();
+
+            assert_eq!(store.query(&filter).unwrap(), expected, "vendor {}", vendor);
+        }
+
+        assert_eq!(store.query(&Filter::new().with_vendor("CAFÉ")).unwrap().len(), 2)
+    }
+
+    #[test]
+    fn first_row_naming_a_column_is_still_a_row() {
+        let layout = Layout::new(vec![Column::Vendor, Column::Memo, Column::Deposit]);
+        let tsv = "Credit\tCash\t50.00\r\n\
+                   Sam Hill Credit Union\t\t500.00\r\n";
+
+        let records = Record::from_tsv_reader_with(tsv.as_bytes(), &layout).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].transaction.vendor, "Credit")
+    }
+
+    #[test]
+    fn first_row_with_bad_date_and_column_name_is_reported() {
+        let tsv = "FF04C3DC-F0FE-472E-8737-0F4034C049F0\t2021-13-08\t\tN\t\tCheck\t\t500.00\t\r\n";
+
+        match Record::from_tsv_reader(tsv.as_bytes()) {
+            Err(Error::Rows(errors)) => assert!(matches!(errors[0], Error::Field { line: Some(1), column: 2, .. })),
+            result => panic!("expected the row to be reported, got {:?}", result)
+        }
+    }
+
+    #[test]
+    fn header_with_unknown_extra_column_is_skipped() {
+        let tsv = "Date\tPayee\tAmount\tRunning Total\r\n\
+                   2021-07-08\tSam Hill Credit Union\t500.00\t500.00\r\n\
+                   2021-07-08\tFake Street Electronics\t-200.00\t300.00\r\n";
+
+        let records = Record::from_tsv_reader(tsv.as_bytes()).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].transaction, Transaction::from(Some("2021-7-8"), None, None, "Fake Street Electronics", "", 200.0, TransactionType::Withdrawal, false).unwrap())
+    }
+
+    #[test]
+    fn layout_with_header_keeps_its_columns() {
+        let layout = Layout::new(vec![Column::Date, Column::Vendor, Column::Amount]).with_header(true);
+        let tsv = "Date\tDescription\tAmt\r\n\
+                   2021-07-08\tSam Hill Credit Union\t500.00\r\n";
+
+        let records = Record::from_tsv_reader_with(tsv.as_bytes(), &layout).unwrap();
+        let csv_records = Record::from_csv_reader(tsv.replace('\t', ",").as_bytes(), &CsvFormat::new().with_layout(layout)).unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].transaction.vendor, "Sam Hill Credit Union");
+        assert_eq!(csv_records[0].transaction, records[0].transaction)
+    }
+
+    #[test]
+    fn qif_cleared_status_ignores_case() {
+        let qif = "!Type:Bank\nD7/8/2021\nT5.00\nCC\n^\nD7/9/2021\nT6.00\nCx\n^\nD7/10/2021\nT7.00\n^\n";
+
+        let records = Record::from_qif_reader(qif.as_bytes()).unwrap();
+
+        assert_eq!(records.iter().map(|record| record.transaction.is_reconciled).collect::<Vec<bool>>(), vec![true, true, false])
+    }
+
+    #[test]
+    fn qif_without_type_header_errors_out() {
+        assert!(matches!(Record::from_qif_reader("D7/8/2021\nT5.00\n^\n".as_bytes()), Err(Error::Invalid { .. })));
+        assert!(Record::from_qif_reader("!Type:Invst\nD7/8/2021\nT5.00\n^\n".as_bytes()).unwrap().is_empty())
+    }
+
+    #[test]
+    fn balances_of_huge_amounts_saturate() {
+        let mut records: Vec<Record> = vec![
+            Record::from("A", Transaction::from(Some("2021-7-8"), None, Some("Income"), "Sam Hill Credit Union", "", 0.0, TransactionType::Deposit, true).unwrap()),
+            Record::from("B", Transaction::from(Some("2021-7-9"), None, Some("Income"), "Sam Hill Credit Union", "", 0.0, TransactionType::Deposit, true).unwrap())
+        ];
+
+        for record in &mut records {
+            record.transaction.amount = Money::from_cents(i64::MAX);
+        }
+
+        let balance = RunningBalance::new(&records);
+
+        assert_eq!(balance.current(), Money::from_cents(i64::MAX));
+        assert_eq!(balance.iter().last().map(|(_, balance)| balance), Some(Money::from_cents(i64::MAX)));
+        assert_eq!(Report::new(&records, Period::Month).rows()[0].deposits, Money::from_cents(i64::MAX))
+    }
+
+    #[test]
+    fn money_saturates_instead_of_overflowing() {
+        assert_eq!(Money::from_cents(i64::MIN).abs(), Money::from_cents(i64::MAX));
+        assert_eq!(Money::from_cents(i64::MIN + 1).saturating_sub(Money::from_cents(5)), Money::from_cents(i64::MIN));
+        assert_eq!(Money::from_cents(250).saturating_add(Money::from_cents(-50)), Money::from_cents(200))
+    }
+
+    #[test]
+    fn transfer_update_keeps_splits_adding_up() {
+        let mut workbook = Workbook::new();
+        let checking = Account::new("Checking");
+        let savings = Account::new("Savings");
+        let (checking_id, savings_id) = (checking.id.clone(), savings.id.clone());
+
+        workbook.add_account(checking).unwrap();
+        workbook.add_account(savings).unwrap();
+
+        workbook.transfer(&checking_id, &savings_id, NaiveDate::from_ymd_opt(2021, 7, 8).unwrap(), Money::from_cents(10000), "").unwrap();
+
+        let account = workbook.account_mut(&savings_id).unwrap();
+        let mut deposit = account.register[0].clone();
+        deposit.transaction.set_splits(vec![Split::new(Some("Savings"), Money::from_cents(6000)), Split::new(Some("Interest"), Money::from_cents(4000))]).unwrap();
+        account.register.update(deposit.clone()).unwrap();
+
+        let mut withdrawal = workbook.account(&checking_id).unwrap().register[0].clone();
+        withdrawal.transaction.amount = Money::from_cents(15000);
+
+        assert!(matches!(workbook.update_record(&checking_id, withdrawal), Err(Error::Splits { .. })));
+        assert_eq!(workbook.account(&checking_id).unwrap().register[0].transaction.amount, Money::from_cents(10000));
+        assert_eq!(workbook.account(&savings_id).unwrap().register[0], deposit)
+    }
+
+    #[test]
+    fn load_workbook_with_bad_splits_errors_out() {
+        let mut transaction = Transaction::from(Some("2021-7-8"), None, None, "Fake Street Market", "", 30.0, TransactionType::Withdrawal, false).unwrap();
+        transaction.splits = vec![Split::new(Some("Groceries"), Money::from_cents(2000))];
+
+        let mut workbook = Workbook::new();
+        workbook.add_account(Account::new("Checking").with_register(Register::try_from(vec![Record::from("A", transaction)]).unwrap())).unwrap();
+
+        let mut json: Vec<u8> = vec![];
+        workbook.to_writer(&mut json).unwrap();
+
+        assert!(matches!(Workbook::from_reader(json.as_slice()), Err(Error::Splits { .. })))
+    }
+
+    #[test]
+    fn negative_amounts_are_lost_in_delimited_formats() {
+        let mut record = Record::from("A", Transaction::from(Some("2021-7-8"), None, None, "Sam Hill Credit Union", "", 5.0, TransactionType::Deposit, false).unwrap());
+        record.transaction.amount = Money::from_cents(-500);
+
+        assert_eq!(Format::Tsv.lost_fields(&record), vec![LostField::Amount]);
+        assert_eq!(Format::Csv.lost_fields(&record), vec![LostField::Amount]);
+        assert!(Format::Bcheck.lost_fields(&record).is_empty());
+
+        // the loss is real, since the file cannot be read back.
+        let mut tsv: Vec<u8> = vec![];
+        Format::Tsv.write(std::slice::from_ref(&record), &mut tsv).unwrap();
+
+        assert!(Format::Tsv.read(&tsv).is_err());
+        assert!(!Conversion::new(vec![record], Format::Csv).is_lossless())
+    }
+
+    #[test]
+    fn qif_reports_text_with_surrounding_spaces() {
+        let record = Record::from("A", Transaction::from(Some("2021-7-8"), None, Some("Gifts "), " Fake Street Electronics", "Head set\t", 200.0, TransactionType::Withdrawal, false).unwrap());
+
+        assert_eq!(Format::Qif.lost_fields(&record), vec![LostField::Category, LostField::Vendor, LostField::Memo]);
+        assert_eq!(Format::Qif.always_lost_fields(), vec![LostField::Id]);
+        assert!(Format::Qif.lost_fields(&Record::from("B", Transaction::from(Some("2021-7-8"), None, Some("Gifts"), "Fake Street Electronics", "Head set", 200.0, TransactionType::Withdrawal, false).unwrap())).is_empty());
+        assert!(Format::Tsv.lost_fields(&record).contains(&LostField::Memo));
+        assert!(!Format::Csv.lost_fields(&record).contains(&LostField::Vendor))
+    }
+
+    #[test]
+    fn failed_save_keeps_the_old_file() {
+        let records = vec![
+            Record::from("FF04C3DC-F0FE-472E-8737-0F4034C049F0", Transaction::from(Some("2021-7-8"), Some(1260), None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap())
+        ];
+
+        let path = std::env::temp_dir().join("failed_save_keeps_the_old_file.bcheck");
+        records.save(&path).unwrap();
+
+        let result = crate::save_vec::write_file(&path, |output| {
+            std::io::Write::write_all(output, b"[{")?;
+            Err(Error::Invalid { value: String::new(), expected: "a write that fails" })
+        });
+
+        assert!(result.is_err());
+        assert_eq!(Record::from_file(&path).unwrap(), records);
+        assert!(!std::env::temp_dir().join("failed_save_keeps_the_old_file.bcheck.tmp").exists())
+    }
+
+    #[test]
+    fn save_through_a_trait_object() {
+        struct Note(&'static str);
+
+        // only the JSON writer is implemented, so TSV is reported as unsupported.
+        impl Save for Note {
+            fn to_writer(&self, writer: &mut dyn std::io::Write) -> Result<(), Error> {
+                Ok(serde_json::to_writer(writer, self.0)?)
+            }
+        }
+
+        let note = Note("Open Account");
+        let register = Register::new();
+        let saveables: Vec<&dyn Save> = vec![&note, &register];
+        let mut buffer: Vec<u8> = vec![];
+
+        saveables[0].to_writer(&mut buffer).unwrap();
+        saveables[1].save(&std::env::temp_dir().join("save_through_a_trait_object.bcheck")).unwrap();
+
+        assert_eq!(buffer, b"\"Open Account\"");
+        assert!(matches!(saveables[0].to_tsv_writer(&mut buffer), Err(Error::Io { .. })))
+    }
+
     #[test]
     fn save_data() {
         let records: Vec<Record> = vec![
@@ -233,7 +1660,7 @@ mod tests {
             Record::from("BB22187E-0BD3-41E8-B3D8-8136BD700865", Transaction::from(Some("2021-7-8"), None, None, "Velociraptor Entertainment", "", 50000 as f64, TransactionType::Deposit, false).unwrap())
         ];
 
-        assert!(!records.save("test.bcheck").is_err())
+        assert!(!records.save(&std::env::temp_dir().join("test.bcheck")).is_err())
     }
 
     #[test]
@@ -244,7 +1671,7 @@ mod tests {
             Record::from("BB22187E-0BD3-41E8-B3D8-8136BD700865", Transaction::from(Some("2021-7-8"), None, None, "Velociraptor Entertainment", "", 50000 as f64, TransactionType::Deposit, false).unwrap())
         ];
 
-        assert!(!records.save_tsv("test.tsv").is_err())
+        assert!(!records.save_tsv(&std::env::temp_dir().join("test.tsv")).is_err())
     }
 
     #[test]
@@ -255,7 +1682,10 @@ mod tests {
             Record::from("BB22187E-0BD3-41E8-B3D8-8136BD700865", Transaction::from(Some("2021-7-8"), None, None, "Velociraptor Entertainment", "", 50000 as f64, TransactionType::Deposit, false).unwrap())
         ];
 
-        let records = Record::from_tsv_file("test.tsv").unwrap();
+        let path = std::env::temp_dir().join("load_records_from_tsv.tsv");
+        expected_records.save_tsv(&path).unwrap();
+
+        let records = Record::from_tsv_file(path).unwrap();
 
         assert_eq!(records, expected_records)
     }
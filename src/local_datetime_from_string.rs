@@ -1,41 +1,35 @@
 use chrono::prelude::*;
 use crate::is_proper_date_format;
+use crate::error::Error;
 
 /// trait that allows object to become a datetime object.
 pub trait LocalDateTimeExt {
-    fn local_datetime(&self) -> Result<DateTime<Local>, String>;
+    fn local_datetime(&self) -> Result<DateTime<Local>, Error>;
 }
 
 impl LocalDateTimeExt for String {
-    fn local_datetime(&self) -> Result<DateTime<Local>, String> {
-        if is_proper_date_format(self) {
-            let naive_date = NaiveDate::parse_from_str(self, crate::transaction::transaction_date_format::FORMAT).unwrap();
-
-            let naive_datetime = naive_date.and_hms_opt(0, 0, 0).unwrap();
-
-            let local_datetime = Local.from_local_datetime(&naive_datetime).unwrap();
-
-            Ok(local_datetime)
-        } else {
-            Err(String::from("String must be in the format of YYYY-MM-DD."))
-        }
-        
+    fn local_datetime(&self) -> Result<DateTime<Local>, Error> {
+        self.as_str().local_datetime()
     }
 }
 
 impl LocalDateTimeExt for str {
-    fn local_datetime(&self) -> Result<DateTime<Local>, String> {
-        if is_proper_date_format(self) {
-            let naive_date = NaiveDate::parse_from_str(self, crate::transaction::transaction_date_format::FORMAT).unwrap();
-
-            let naive_datetime = naive_date.and_hms_opt(0, 0, 0).unwrap();
-
-            let local_datetime = Local.from_local_datetime(&naive_datetime).unwrap();
+    fn local_datetime(&self) -> Result<DateTime<Local>, Error> {
+        if !is_proper_date_format(self) {
+            return Err(Error::Date(self.to_string()))
+        }
 
-            Ok(local_datetime)
-        } else {
-            Err(String::from("String must be in the format of YYYY-MM-DD."))
+        match NaiveDate::parse_from_str(self, crate::transaction::transaction_date_format::FORMAT) {
+            Ok(naive_date) => Ok(local_midnight(naive_date)),
+            Err(_) => Err(Error::Date(self.to_string()))
         }
-        
     }
-}
\ No newline at end of file
+}
+
+/// create a local datetime at the start of the given day, which is how dates are stored in records.
+pub fn local_midnight(date: NaiveDate) -> DateTime<Local> {
+    let naive_datetime = date.and_hms_opt(0, 0, 0).unwrap();
+
+    // midnight can be skipped by daylight saving time in some zones, so fall back to treating the time as UTC.
+    Local.from_local_datetime(&naive_datetime).earliest().unwrap_or_else(|| Local.from_utc_datetime(&naive_datetime))
+}
@@ -0,0 +1,52 @@
+// import fmt, so that lost fields can be shown to people.
+use std::fmt;
+
+/// Represents part of a record that a file format cannot hold as it is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LostField {
+    /// the id, which is replaced with a new one when the file is read.
+    Id,
+
+    /// the category, which has characters the format cannot hold, such as line breaks or spaces at either end.
+    Category,
+
+    /// the vendor, which has characters the format cannot hold, such as line breaks or spaces at either end.
+    Vendor,
+
+    /// the memo, which has characters the format cannot hold, such as line breaks or spaces at either end.
+    Memo,
+
+    /// a negative amount, which the format either cannot read back or turns into the opposite type of transaction.
+    Amount,
+
+    /// whether the record is reconciled.
+    Reconciled,
+
+    /// the splits, which leaves only the category of the transaction.
+    Splits,
+
+    /// the link to the other half of a transfer.
+    TransferId
+}
+
+impl LostField {
+    /// the name used for the field in messages.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Category => "category",
+            Self::Vendor => "vendor",
+            Self::Memo => "memo",
+            Self::Amount => "amount",
+            Self::Reconciled => "reconciled",
+            Self::Splits => "splits",
+            Self::TransferId => "transfer_id"
+        }
+    }
+}
+
+impl fmt::Display for LostField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,257 @@
+// import custom error, so that parsing failures can be reported.
+use crate::money_parse_error::MoneyParseError;
+
+// import Serde, so that amounts can be read and written as the plain numbers BCheckbook uses.
+use serde::{ Serialize, Deserialize, Serializer, Deserializer, de::{ self, Visitor } };
+
+// import things needed to do arithmetic, display, and parse amounts.
+use std::{ fmt, iter::Sum, ops::{ Add, AddAssign, Neg, Sub, SubAssign }, str::FromStr };
+
+/**
+ * Represent an amount of money exactly, as a whole number of cents.
+ * Unlike floating point numbers, adding up thousands of amounts never drifts by fractions of a cent.
+ * In JSON, amounts are written as plain numbers, like BCheckbook does.
+ *
+ * The arithmetic operators panic if the result is too large to be stored,
+ * so use methods like checked_add() or saturating_add() if amounts come from somewhere that cannot be trusted.
+ * Balances and reports made by this crate saturate, so that a file with absurd amounts cannot crash a program.
+ * # Example
+ * ```
+ * use bcheck::Money;
+ *
+ * let amount: Money = "19.99".parse().unwrap();
+ *
+ * assert_eq!(amount + Money::from_cents(1), Money::from_cents(2000));
+ * assert_eq!(amount.to_string(), "19.99");
+ * ```
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i64);
+
+impl Money {
+    /// an amount of nothing.
+    pub const ZERO: Money = Money(0);
+
+    /// create an amount from a number of cents.
+    pub const fn from_cents(cents: i64) -> Money {
+        Money(cents)
+    }
+
+    /**
+     * create an amount from a floating point number, rounding to the nearest cent.
+     * None is returned if the number is not finite or is too large.
+     */
+    pub fn from_f64(amount: f64) -> Option<Money> {
+        if !amount.is_finite() {
+            return None
+        }
+
+        // go through the shortest decimal form of the number, so that 0.1 becomes 10 cents, instead of what 0.1 * 100 gives.
+        parse(&amount.to_string(), true).ok()
+    }
+
+    /// the number of cents in the amount.
+    pub const fn cents(&self) -> i64 {
+        self.0
+    }
+
+    /// the amount as a floating point number, which is only meant for display and interoperability.
+    pub fn to_f64(&self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    /// the amount without its sign, which is the largest amount possible if the amount is the smallest one possible.
+    pub fn abs(&self) -> Money {
+        Money(self.0.saturating_abs())
+    }
+
+    /// whether the amount is less than zero.
+    pub const fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    /// whether the amount is zero.
+    pub const fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// add two amounts, returning None if the result is too large.
+    pub fn checked_add(&self, other: Money) -> Option<Money> {
+        self.0.checked_add(other.0).map(Money)
+    }
+
+    /// subtract an amount, returning None if the result is too large.
+    pub fn checked_sub(&self, other: Money) -> Option<Money> {
+        self.0.checked_sub(other.0).map(Money)
+    }
+
+    /// multiply an amount by a whole number, returning None if the result is too large.
+    pub fn checked_mul(&self, factor: i64) -> Option<Money> {
+        self.0.checked_mul(factor).map(Money)
+    }
+
+    /// add two amounts, stopping at the largest or smallest amount possible instead of overflowing.
+    pub fn saturating_add(&self, other: Money) -> Money {
+        Money(self.0.saturating_add(other.0))
+    }
+
+    /// subtract an amount, stopping at the largest or smallest amount possible instead of overflowing.
+    pub fn saturating_sub(&self, other: Money) -> Money {
+        Money(self.0.saturating_sub(other.0))
+    }
+
+    /// flip the sign of an amount, returning None if the result is too large.
+    pub fn checked_neg(&self) -> Option<Money> {
+        self.0.checked_neg().map(Money)
+    }
+}
+
+// parse a decimal number into cents. Extra decimal places are rounded when round is true, and rejected otherwise.
+fn parse(s: &str, round: bool) -> Result<Money, MoneyParseError> {
+    let trimmed = s.trim();
+
+    if trimmed.is_empty() {
+        return Err(MoneyParseError::Empty)
+    }
+
+    let (is_negative, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed))
+    };
+
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+    if (whole.is_empty() && fraction.is_empty()) || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return Err(MoneyParseError::InvalidNumber(s.to_string()))
+    }
+
+    let out_of_range = || MoneyParseError::OutOfRange(s.to_string());
+
+    let mut cents: i64 = 0;
+
+    for digit in whole.chars().chain(fraction.chars().chain("00".chars()).take(2)) {
+        cents = cents.checked_mul(10).and_then(|cents| cents.checked_add(digit.to_digit(10).unwrap() as i64)).ok_or_else(out_of_range)?;
+    }
+
+    let extra_digits = fraction.get(2..).unwrap_or("");
+
+    if extra_digits.chars().any(|c| c != '0') {
+        if !round {
+            return Err(MoneyParseError::TooPrecise(s.to_string()))
+        }
+
+        // round half away from zero, like people do by hand.
+        if extra_digits.starts_with(['5', '6', '7', '8', '9']) {
+            cents = cents.checked_add(1).ok_or_else(out_of_range)?;
+        }
+    }
+
+    Ok(Money(if is_negative { -cents } else { cents }))
+}
+
+impl FromStr for Money {
+    type Err = MoneyParseError;
+
+    /// parse a decimal number, such as 19.99 or -5. More than two decimal places are only allowed if they are zeros.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s, false)
+    }
+}
+
+// implement trait needed to display amount as string, always with two decimal places.
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.is_negative() { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+
+        write!(f, "{}{}.{:02}", sign, cents / 100, cents % 100)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        self.checked_add(other).expect("amount is too large")
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        self.checked_sub(other).expect("amount is too large")
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        self.checked_neg().expect("amount is too large")
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, other: Money) {
+        *self = *self - other;
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.copied().sum()
+    }
+}
+
+// write amounts as plain JSON numbers, like 500.0, so that BCheckbook can read them.
+impl Serialize for Money {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_f64(self.to_f64())
+    }
+}
+
+// read amounts from JSON numbers, or from strings, rounding to the nearest cent.
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D>(deserializer: D) -> Result<Money, D::Error> where D: Deserializer<'de> {
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+struct MoneyVisitor;
+
+impl Visitor<'_> for MoneyVisitor {
+    type Value = Money;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an amount of money")
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Money, E> where E: de::Error {
+        value.checked_mul(100).map(Money).ok_or_else(|| E::custom(MoneyParseError::OutOfRange(value.to_string())))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Money, E> where E: de::Error {
+        i64::try_from(value).ok().and_then(|value| value.checked_mul(100)).map(Money).ok_or_else(|| E::custom(MoneyParseError::OutOfRange(value.to_string())))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Money, E> where E: de::Error {
+        Money::from_f64(value).ok_or_else(|| E::custom(MoneyParseError::OutOfRange(value.to_string())))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Money, E> where E: de::Error {
+        parse(value, true).map_err(E::custom)
+    }
+}
@@ -0,0 +1,29 @@
+use std::{ error::Error, fmt };
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MoneyParseError {
+    /// nothing was given.
+    Empty,
+
+    /// the string was not a decimal number.
+    InvalidNumber(String),
+
+    /// the string had more than two decimal places, which cannot be stored without rounding.
+    TooPrecise(String),
+
+    /// the amount is too large to be stored.
+    OutOfRange(String)
+}
+
+impl fmt::Display for MoneyParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no amount was given"),
+            Self::InvalidNumber(s) => write!(f, "{} is not a valid number", s),
+            Self::TooPrecise(s) => write!(f, "{} has more than two decimal places", s),
+            Self::OutOfRange(s) => write!(f, "{} is too large", s)
+        }
+    }
+}
+
+impl Error for MoneyParseError {}
@@ -0,0 +1,140 @@
+// import custom types, so that statement entries can become records.
+use crate::{ error::Error, local_datetime_from_string::local_midnight, record::Record, transaction::Transaction, transaction_type::TransactionType };
+
+// import chrono, so that posting dates can be read.
+use chrono::NaiveDate;
+
+// import Money, so that amounts can be stored exactly.
+use crate::money::Money;
+
+// import uuid crate, so that ids can be made from transaction ids given by the bank.
+use uuid::Uuid;
+
+// import HashMap, to hold the elements of a transaction while it is being read.
+use std::collections::HashMap;
+
+/**
+ * turn the contents of an OFX or QFX statement into records.
+ * Both the SGML style of OFX 1.x, where elements do not need to be closed, and the XML style of OFX 2.x are supported.
+ * Record ids are made from the account and FITID of each transaction, so loading the same statement twice gives the same ids.
+ */
+pub(crate) fn records_from_ofx(text: &str) -> Result<Vec<Record>, Error> {
+    let mut records: Vec<Record> = vec![];
+    let mut bank_id = String::new();
+    let mut account_id = String::new();
+    let mut transaction: Option<(usize, HashMap<String, String>)> = None;
+
+    for tag in tags(text) {
+        match (tag.name.as_str(), tag.is_closing) {
+            ("STMTTRN", false) => transaction = Some((tag.line, HashMap::new())),
+            ("STMTTRN", true) => if let Some((line, elements)) = transaction.take() {
+                records.push(record_from_elements(line, &format!("{}:{}", bank_id, account_id), &elements)?);
+            },
+            (name, false) if !tag.value.is_empty() => match transaction.as_mut() {
+                Some((_, elements)) => {
+                    // keep the first value, so that the NAME of a PAYEE does not replace the NAME of the transaction.
+                    elements.entry(name.to_string()).or_insert(tag.value);
+                },
+                None if name == "BANKID" => bank_id = tag.value,
+                None if name == "ACCTID" => account_id = tag.value,
+                None => ()
+            },
+            _ => ()
+        }
+    }
+
+    // OFX 1.x files are not always well formed, so make sure the last transaction is not lost.
+    if let Some((line, elements)) = transaction.take() {
+        records.push(record_from_elements(line, &format!("{}:{}", bank_id, account_id), &elements)?);
+    }
+
+    Ok(records)
+}
+
+// create a record from the elements of a STMTTRN aggregate.
+fn record_from_elements(line: usize, account: &str, elements: &HashMap<String, String>) -> Result<Record, Error> {
+    let element = |name: &str| -> Result<&String, Error> {
+        elements.get(name).ok_or_else(|| Error::Entry { line, name: name.to_string(), value: String::new(), source: None })
+    };
+
+    let invalid = |name: &str, value: &str, source: Error| Error::Entry { line, name: name.to_string(), value: value.to_string(), source: Some(Box::new(source)) };
+
+    let fitid = element("FITID")?;
+
+    let date_posted = element("DTPOSTED")?;
+    let date = date_posted.get(..8).and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok()).ok_or_else(|| invalid("DTPOSTED", date_posted, Error::Date(date_posted.clone())))?;
+
+    // some banks use a comma for the decimal point, which the OFX specification allows.
+    let amount_string = element("TRNAMT")?;
+    let amount = match amount_string.replace(',', ".").parse::<Money>() {
+        Ok(amount) => amount,
+        Err(error) => return Err(invalid("TRNAMT", amount_string, Error::Amount { value: amount_string.clone(), source: error }))
+    };
+
+    Ok(Record {
+        id: Uuid::new_v5(&Uuid::NAMESPACE_URL, format!("ofx:{}:{}", account, fitid).as_bytes()).hyphenated().to_string(),
+        transaction: Transaction {
+            date: local_midnight(date),
+            check_number: elements.get("CHECKNUM").and_then(|check_number| check_number.parse::<u32>().ok()),
+            category: None,
+            vendor: elements.get("NAME").cloned().unwrap_or_default(),
+            memo: elements.get("MEMO").cloned().unwrap_or_default(),
+            amount: amount.abs(),
+            transaction_type: if amount.is_negative() {
+                TransactionType::Withdrawal
+            } else {
+                TransactionType::Deposit
+            },
+            is_reconciled: false,
+            splits: vec![]
+        },
+        transfer_id: None
+    })
+}
+
+// a tag, along with the text that follows it and the line it is on.
+struct Tag {
+    name: String,
+    is_closing: bool,
+    value: String,
+    line: usize
+}
+
+// split OFX into tags, skipping headers, processing instructions, and comments.
+fn tags(text: &str) -> Vec<Tag> {
+    let mut tags: Vec<Tag> = vec![];
+    let mut line = 1;
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        line += rest[..start].matches('\n').count();
+        rest = &rest[start + 1..];
+
+        let Some(end) = rest.find('>') else {
+            break
+        };
+
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue
+        }
+
+        let value_end = rest.find('<').unwrap_or(rest.len());
+
+        tags.push(Tag {
+            name: tag.trim_start_matches('/').trim().to_uppercase(),
+            is_closing: tag.starts_with('/'),
+            value: decode_entities(rest[..value_end].trim()),
+            line
+        });
+    }
+
+    tags
+}
+
+// replace the character entities used by XML and SGML.
+fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&nbsp;", " ").replace("&amp;", "&")
+}
@@ -0,0 +1,70 @@
+// import chrono, so that dates can be put into periods.
+use chrono::{ Datelike, Months, NaiveDate };
+
+// import Serde, so that periods can be stored along with things like budgets.
+use serde::{ Serialize, Deserialize };
+
+/// Represents the span of time that records are grouped by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    /// calendar months.
+    Month,
+
+    /// calendar quarters, starting in January, April, July, and October.
+    Quarter,
+
+    /// calendar years.
+    Year,
+
+    /// a single span from start to end, including both days.
+    Custom { start: NaiveDate, end: NaiveDate }
+}
+
+impl Period {
+    /// the first and last day of the period that contains the given date. Custom periods give None for dates outside of them.
+    pub fn bounds(&self, date: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        match self {
+            Self::Month => Some(month_bounds(date.year(), date.month())),
+            Self::Quarter => {
+                let first_month = (date.month0() / 3) * 3 + 1;
+
+                Some((month_bounds(date.year(), first_month).0, month_bounds(date.year(), first_month + 2).1))
+            },
+            Self::Year => Some((NaiveDate::from_ymd_opt(date.year(), 1, 1)?, NaiveDate::from_ymd_opt(date.year(), 12, 31)?)),
+            Self::Custom { start, end } => if (start..=end).contains(&&date) {
+                Some((*start, *end))
+            } else {
+                None
+            }
+        }
+    }
+
+    /// the first day of the period after the one that starts on the given date, or None for custom periods.
+    pub fn next_start(&self, start: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Self::Month => start.checked_add_months(Months::new(1)),
+            Self::Quarter => start.checked_add_months(Months::new(3)),
+            Self::Year => start.checked_add_months(Months::new(12)),
+            Self::Custom { .. } => None
+        }
+    }
+
+    /// a short name for the period starting on the given date, such as 2021-07, 2021-Q3, or 2021.
+    pub fn label(&self, start: NaiveDate) -> String {
+        match self {
+            Self::Month => start.format("%Y-%m").to_string(),
+            Self::Quarter => format!("{}-Q{}", start.year(), start.month0() / 3 + 1),
+            Self::Year => start.year().to_string(),
+            Self::Custom { start, end } => format!("{} to {}", start, end)
+        }
+    }
+}
+
+// the first and last day of the given month.
+fn month_bounds(year: i32, month: u32) -> (NaiveDate, NaiveDate) {
+    let first_day = NaiveDate::from_ymd_opt(year, month, 1).expect("month should be valid");
+    let last_day = first_day.checked_add_months(Months::new(1)).and_then(|next_month| next_month.pred_opt()).unwrap_or(NaiveDate::MAX);
+
+    (first_day, last_day)
+}
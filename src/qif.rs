@@ -0,0 +1,228 @@
+// import custom types, so that QIF entries can become records and back.
+use crate::{ error::Error, local_datetime_from_string::local_midnight, record::{ Record, default_id }, split::Split, transaction::Transaction, transaction_type::TransactionType };
+
+// import chrono, so that dates can be read and written.
+use chrono::{ Datelike, NaiveDate };
+
+// import Money, so that amounts can be stored exactly.
+use crate::money::Money;
+
+/**
+ * turn the contents of a QIF file into records.
+ * Only the transactions in sections for bank, cash, and credit card accounts are read, and every other section is skipped.
+ * Transactions marked as cleared or reconciled are both treated as reconciled, and split lines become splits.
+ * Text without any !Type header is not a QIF file, so it is an error rather than an empty list.
+ */
+pub(crate) fn records_from_qif(text: &str) -> Result<Vec<Record>, Error> {
+    let mut records: Vec<Record> = vec![];
+    let mut is_reading = false;
+    let mut has_type = false;
+    let mut entry: Vec<(usize, char, &str)> = vec![];
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+
+        let Some(code) = line.chars().next() else {
+            continue
+        };
+
+        if code == '!' {
+            let header = line.to_lowercase().replace(' ', "");
+
+            if header.starts_with("!type:") {
+                has_type = true;
+                is_reading = matches!(header.as_str(), "!type:bank" | "!type:cash" | "!type:ccard" | "!type:otha" | "!type:othl");
+            } else if header.starts_with("!account") {
+                is_reading = false;
+            }
+
+            continue
+        }
+
+        if !is_reading {
+            continue
+        }
+
+        if code == '^' {
+            if !entry.is_empty() {
+                records.push(record_from_entry(&entry)?);
+                entry.clear();
+            }
+        } else {
+            entry.push((index + 1, code, &line[code.len_utf8()..]));
+        }
+    }
+
+    if !has_type {
+        return Err(Error::Invalid { value: text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default().to_string(), expected: "a QIF file with a !Type header" })
+    }
+
+    // the last transaction is not always ended properly, so make sure it is not lost.
+    if !entry.is_empty() {
+        records.push(record_from_entry(&entry)?);
+    }
+
+    Ok(records)
+}
+
+/**
+ * turn records into a QIF file for a bank account.
+ * QIF is line based, so line breaks in text are replaced with spaces, and splits are written as split lines.
+ */
+pub(crate) fn string_from_records(records: &[Record]) -> String {
+    let mut qif_string = String::from("!Type:Bank\r\n");
+
+    for record in records {
+        let transaction = &record.transaction;
+        let date = transaction.date.date_naive();
+
+        qif_string.push_str(&format!("D{}/{}/{}\r\n", date.month(), date.day(), date.year()));
+        qif_string.push_str(&format!("T{}{}\r\n", if transaction.transaction_type == TransactionType::Withdrawal { "-" } else { "" }, transaction.amount));
+
+        if let Some(check_number) = transaction.check_number {
+            qif_string.push_str(&format!("N{}\r\n", check_number));
+        }
+
+        qif_string.push_str(&format!("P{}\r\n", single_line(&transaction.vendor)));
+
+        if !transaction.memo.is_empty() {
+            qif_string.push_str(&format!("M{}\r\n", single_line(&transaction.memo)));
+        }
+
+        if let Some(category) = &transaction.category {
+            qif_string.push_str(&format!("L{}\r\n", single_line(category)));
+        }
+
+        if transaction.is_reconciled {
+            qif_string.push_str("CX\r\n");
+        }
+
+        for split in &transaction.splits {
+            qif_string.push_str(&format!("S{}\r\n", split.category.as_deref().map(single_line).unwrap_or_default()));
+
+            if !split.memo.is_empty() {
+                qif_string.push_str(&format!("E{}\r\n", single_line(&split.memo)));
+            }
+
+            qif_string.push_str(&format!("${}\r\n", if transaction.transaction_type == TransactionType::Withdrawal { -split.amount } else { split.amount }));
+        }
+
+        qif_string.push_str("^\r\n");
+    }
+
+    qif_string
+}
+
+// create a record from the lines of a single transaction.
+fn record_from_entry(entry: &[(usize, char, &str)]) -> Result<Record, Error> {
+    let first_line = entry[0].0;
+
+    let field = |code: char| entry.iter().find(|(_, c, _)| *c == code).map(|(line, _, value)| (*line, value.trim()));
+
+    let invalid = |line: usize, code: char, value: &str, source: Error| Error::Entry { line, name: code.to_string(), value: value.to_string(), source: Some(Box::new(source)) };
+
+    let (date_line, date_string) = field('D').ok_or_else(|| Error::Entry { line: first_line, name: String::from("D"), value: String::new(), source: None })?;
+    let date = date_from_qif(date_string).ok_or_else(|| invalid(date_line, 'D', date_string, Error::Date(date_string.to_string())))?;
+
+    // newer versions of Quicken write the amount twice, with U holding more precision.
+    let (amount_line, amount_code, amount_string) = match field('T') {
+        Some((line, amount)) => (line, 'T', amount),
+        None => match field('U') {
+            Some((line, amount)) => (line, 'U', amount),
+            None => return Err(Error::Entry { line: first_line, name: String::from("T"), value: String::new(), source: None })
+        }
+    };
+
+    let amount = match amount_string.replace(',', "").parse::<Money>() {
+        Ok(amount) => amount,
+        Err(error) => return Err(invalid(amount_line, amount_code, amount_string, Error::Amount { value: amount_string.to_string(), source: error }))
+    };
+
+    // each split starts with its category, followed by its memo and amount.
+    let mut splits: Vec<Split> = vec![];
+
+    for (line, code, value) in entry {
+        let value = value.trim();
+
+        match code {
+            'S' => splits.push(Split::new(Some(value).filter(|category| !category.is_empty()), Money::ZERO)),
+            'E' => if let Some(split) = splits.last_mut() {
+                split.memo = value.to_string();
+            },
+            '$' => if let Some(split) = splits.last_mut() {
+                let split_amount = value.replace(',', "").parse::<Money>().map_err(|error| invalid(*line, '$', value, Error::Amount { value: value.to_string(), source: error }))?;
+
+                // split amounts go in the same direction as the transaction.
+                split.amount = if amount.is_negative() { -split_amount } else { split_amount };
+            },
+            _ => {}
+        }
+    }
+
+    let mut transaction = Transaction {
+        date: local_midnight(date),
+        // check numbers can also be things like ATM or DEP, which are not kept.
+        check_number: field('N').and_then(|(_, check_number)| check_number.parse::<u32>().ok()),
+        category: field('L').map(|(_, category)| category.to_string()).filter(|category| !category.is_empty()),
+        vendor: field('P').map(|(_, vendor)| vendor.to_string()).unwrap_or_default(),
+        memo: field('M').map(|(_, memo)| memo.to_string()).unwrap_or_default(),
+        amount: amount.abs(),
+        transaction_type: if amount.is_negative() {
+            TransactionType::Withdrawal
+        } else {
+            TransactionType::Deposit
+        },
+        is_reconciled: field('C').is_some_and(|(_, status)| matches!(status.to_lowercase().as_str(), "x" | "r" | "*" | "c")),
+        splits: vec![]
+    };
+
+    if let Some(&(split_line, _, split_category)) = entry.iter().find(|(_, code, _)| *code == 'S') {
+        transaction.set_splits(splits).map_err(|error| invalid(split_line, 'S', split_category, error))?;
+    }
+
+    Ok(Record {
+        id: default_id(),
+        transaction,
+        transfer_id: None
+    })
+}
+
+/**
+ * read the date formats commonly found in QIF files.
+ * An apostrophe before the year, as in 7/8'21, means the year is in the 2000s,
+ * while two digit years after a slash are treated as being in the 1900s from 70 onwards.
+ */
+fn date_from_qif(s: &str) -> Option<NaiveDate> {
+    let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+
+    // some programs use ISO dates.
+    if let Ok(date) = NaiveDate::parse_from_str(&s, "%Y-%m-%d") {
+        return Some(date)
+    }
+
+    let is_apostrophe_year = s.contains('\'');
+    let parts: Vec<&str> = s.split(['/', '\'', '-', '.']).collect();
+
+    if parts.len() != 3 {
+        return None
+    }
+
+    let month = parts[0].parse::<u32>().ok()?;
+    let day = parts[1].parse::<u32>().ok()?;
+    let year = parts[2].parse::<i32>().ok()?;
+
+    let year = match (parts[2].len(), is_apostrophe_year) {
+        (4, _) => year,
+        (1 | 2, true) => 2000 + year,
+        (1 | 2, false) if year >= 70 => 1900 + year,
+        (1 | 2, false) => 2000 + year,
+        _ => return None
+    };
+
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+// replace line breaks, since each line in a QIF file is a field.
+fn single_line(s: &str) -> String {
+    s.replace("\r\n", " ").replace(['\r', '\n'], " ")
+}
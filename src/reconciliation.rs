@@ -0,0 +1,180 @@
+// import custom types, so that a register can be reconciled against a statement.
+use crate::{ discrepancy::{ Discrepancy, DiscrepancyFinder }, error::Error, money::Money, record::Record, register::Register, running_balance::RunningBalance };
+
+// import chrono, so that the statement date can be given.
+use chrono::NaiveDate;
+
+// import HashSet, to keep track of the records that have been ticked off.
+use std::collections::HashSet;
+
+/**
+ * Reconciles a register against a bank statement, like balancing a paper checkbook.
+ * Records that are not reconciled yet and are dated on or before the statement date are listed,
+ * and each one that appears on the statement is ticked off as cleared, until the difference is zero.
+ * # Example
+ * ```
+ * use bcheck::{ Money, Reconciliation, Register };
+ * use chrono::NaiveDate;
+ *
+ * let mut register = Register::new();
+ * let mut reconciliation = Reconciliation::new(&register, NaiveDate::from_ymd_opt(2021, 7, 31).unwrap(), Money::ZERO);
+ *
+ * for record in register.records() {
+ *     // tick off records as they are found on the statement.
+ *     let _ = reconciliation.toggle(&record.id);
+ * }
+ *
+ * if reconciliation.is_balanced() {
+ *     reconciliation.complete(&mut register).unwrap();
+ * }
+ * ```
+ */
+#[derive(Clone, Debug)]
+pub struct Reconciliation {
+    statement_date: NaiveDate,
+    ending_balance: Money,
+    starting_balance: Money,
+    candidates: Vec<Record>,
+    cleared: HashSet<String>
+}
+
+impl Reconciliation {
+    /// start reconciling the given register against a statement that ends on the given date with the given balance.
+    pub fn new(register: &Register, statement_date: NaiveDate, ending_balance: Money) -> Reconciliation {
+        Reconciliation {
+            statement_date,
+            ending_balance,
+            starting_balance: RunningBalance::new(register).reconciled(),
+            candidates: register.iter().filter(|record| !record.transaction.is_reconciled && record.transaction.date.date_naive() <= statement_date).cloned().collect(),
+            cleared: HashSet::new()
+        }
+    }
+
+    /// set the balance the account had before the first record, which is added to the balance of the records already reconciled.
+    pub fn with_opening_balance(mut self, opening_balance: Money) -> Reconciliation {
+        self.starting_balance = self.starting_balance.saturating_add(opening_balance);
+        self
+    }
+
+    /// the last day covered by the statement.
+    pub fn statement_date(&self) -> NaiveDate {
+        self.statement_date
+    }
+
+    /// the balance the statement ends with.
+    pub fn ending_balance(&self) -> Money {
+        self.ending_balance
+    }
+
+    /// the balance of the records that were reconciled before this statement.
+    pub fn starting_balance(&self) -> Money {
+        self.starting_balance
+    }
+
+    /// the records that can be ticked off, in date order.
+    pub fn candidates(&self) -> &[Record] {
+        &self.candidates
+    }
+
+    /// the records that have been ticked off, in date order.
+    pub fn cleared(&self) -> impl Iterator<Item = &Record> {
+        self.candidates.iter().filter(|record| self.cleared.contains(&record.id))
+    }
+
+    /// the records that have not been ticked off, in date order.
+    pub fn uncleared(&self) -> impl Iterator<Item = &Record> {
+        self.candidates.iter().filter(|record| !self.cleared.contains(&record.id))
+    }
+
+    /// whether the record with the given id has been ticked off.
+    pub fn is_cleared(&self, id: &str) -> bool {
+        self.cleared.contains(id)
+    }
+
+    /// tick off the record with the given id, failing if it is not one of the candidates.
+    pub fn clear(&mut self, id: &str) -> Result<(), Error> {
+        if !self.candidates.iter().any(|record| record.id == id) {
+            return Err(Error::UnknownId(id.to_string()))
+        }
+
+        self.cleared.insert(id.to_string());
+
+        Ok(())
+    }
+
+    /// take the tick off the record with the given id.
+    pub fn unclear(&mut self, id: &str) {
+        self.cleared.remove(id);
+    }
+
+    /// tick off the record with the given id if it is not ticked, and take the tick off otherwise, returning whether it is now cleared.
+    pub fn toggle(&mut self, id: &str) -> Result<bool, Error> {
+        if self.cleared.remove(id) {
+            Ok(false)
+        } else {
+            self.clear(id).map(|_| true)
+        }
+    }
+
+    /// tick off every candidate.
+    pub fn clear_all(&mut self) {
+        self.cleared = self.candidates.iter().map(|record| record.id.clone()).collect();
+    }
+
+    /// the balance of the records already reconciled and the records ticked off.
+    pub fn cleared_balance(&self) -> Money {
+        self.cleared().fold(self.starting_balance, |balance, record| balance.saturating_add(record.transaction.signed_amount()))
+    }
+
+    /// how far the cleared balance is from the statement. It is positive when the statement shows more money than the register.
+    pub fn difference(&self) -> Money {
+        self.ending_balance.saturating_sub(self.cleared_balance())
+    }
+
+    /// whether the cleared balance matches the statement.
+    pub fn is_balanced(&self) -> bool {
+        self.difference().is_zero()
+    }
+
+    /// a finder that treats the cleared records as counted and the rest of the candidates as possibly missed, for when explain() is not enough.
+    pub fn discrepancy_finder(&self) -> DiscrepancyFinder<'_> {
+        DiscrepancyFinder::from_parts(self.cleared().collect(), self.uncleared().collect())
+    }
+
+    /// propose reasons the cleared balance does not match the statement, with the simplest ones first.
+    pub fn explain(&self) -> Vec<Discrepancy> {
+        self.discrepancy_finder().find(self.difference())
+    }
+
+    /**
+     * mark every cleared record in the register as reconciled.
+     * Nothing is changed unless the cleared balance matches the statement and every cleared record is still in the register as it was when it was listed,
+     * so the register is never left partly reconciled.
+     */
+    pub fn complete(&self, register: &mut Register) -> Result<(), Error> {
+        if !self.is_balanced() {
+            return Err(Error::Unbalanced(self.difference()))
+        }
+
+        let mut records: Vec<Record> = vec![];
+
+        for candidate in self.cleared() {
+            match register.get(&candidate.id) {
+                Some(record) if record.transaction == candidate.transaction => {
+                    let mut record = record.clone();
+                    record.transaction.is_reconciled = true;
+
+                    records.push(record);
+                },
+                Some(_) => return Err(Error::Changed(candidate.id.clone())),
+                None => return Err(Error::UnknownId(candidate.id.clone()))
+            }
+        }
+
+        for record in records {
+            register.update(record)?;
+        }
+
+        Ok(())
+    }
+}
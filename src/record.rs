@@ -1,5 +1,7 @@
 // import custom types, so they can be used.
 use crate::transaction::Transaction;
+use crate::error::Error;
+use crate::{ csv_format::CsvFormat, layout::Layout, ofx, qif };
 
 // import serde, for easy serialization and deserialization
 use serde::{ Serialize, Deserialize };
@@ -8,10 +10,7 @@ use serde::{ Serialize, Deserialize };
 use uuid::Uuid;
 
 // import File and io stuff, so that data can be loaded from a file.
-use std::{fs::File, fmt, io::{ self, Read } };
-
-// import serde_json crate, to facilitate deserialization from JSON.
-use serde_json;
+use std::{fs::File, fmt, io::{ BufRead, BufReader, Read }, path::Path, str::FromStr };
 
 /// Represents an entry in a check register
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialOrd, Ord)]
@@ -22,6 +21,13 @@ pub struct Record {
 
     /// the record's transaction
     pub transaction: Transaction,
+
+    /**
+     * the id shared by both halves of a transfer between accounts, which is absent for other records.
+     * BCheckbook does not know about transfers, so it is only written when present.
+     */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer_id: Option<String>
 }
 
 impl Record {
@@ -29,7 +35,8 @@ impl Record {
     pub fn new() -> Record {
         Record {
             id: default_id(),
-            transaction: Transaction::new()
+            transaction: Transaction::new(),
+            transfer_id: None
         }
     }
 
@@ -37,7 +44,7 @@ impl Record {
      * Create a record object with given values.
      * If id is an empty String, an id will be generated for you.
      * # Example
-     * ```let record = Record::from("FF04C3DC-F0FE-472E-8737-0F4034C049F0", Transaction::from(Some("2021-7-8"), Some(1260 as u32), String::from("Sam Hill Credit Union"), String::from("Open Account"), OrderedFloat::<f64>(500 as f64), TransactionType::DEPOSIT, false).unwrap());```
+     * ```let record = Record::from("FF04C3DC-F0FE-472E-8737-0F4034C049F0", Transaction::from(Some("2021-7-8"), Some(1260), None, "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false).unwrap());```
     */
     pub fn from(id: &str, transaction: Transaction) -> Record {
         Record {
@@ -48,26 +55,38 @@ impl Record {
                     String::from(id)
                 }
             },
-            transaction
+            transaction,
+            transfer_id: None
         }
     }
 
     /**load vector containing Records from a given file path.
      * This method attempts to read a file containing record data, returning a vector if successful, but will give out an error if something goes wrong, either with loading the file or parsing it.
     */
-    pub fn from_file(f: &str) -> Result<Vec<Record>, String> {
-        match file_contents_from(f) {
-            Ok(content) => {
-                match serde_json::from_str::<Vec<Record>>(&content) {
-                    Ok(decoded_records) => Ok(decoded_records),
-                    Err(error) => Err(format!("{}", error))
-                }
-            },
-            Err(error) => Err(format!("{}", error))
+    pub fn from_file<P: AsRef<Path>>(f: P) -> Result<Vec<Record>, Error> {
+        let file = open_file(&f)?;
+
+        Record::from_reader(file).map_err(|error| error.with_path(f))
+    }
+
+    /** load vector containing Records from anything that can be read, such as a file, network response, or buffer in memory.
+     * The data is expected to be JSON, like what is found in a bcheck file, and an error is given if the splits of a transaction do not add up to its amount.
+    */
+    pub fn from_reader<R: Read>(reader: R) -> Result<Vec<Record>, Error> {
+        let records: Vec<Record> = serde_json::from_reader(BufReader::new(reader))?;
+
+        for record in &records {
+            record.transaction.validate_splits()?;
         }
+
+        Ok(records)
     }
 
-    /// create a record directly from a string.
+    /**
+     * create a record directly from a string.
+     * This is lenient, so fields that cannot be read fall back to default values.
+     * If problems need to be known about, use parse() or try_from() instead.
+     */
     pub fn from_string(s: &str) -> Record {
         let components: Vec<&str> = s.split('\t').collect();
 
@@ -82,36 +101,166 @@ impl Record {
             } else {
                 components[0].to_string()
             },
-            transaction: Transaction::from_string(&transaction_string)
+            transaction: Transaction::from_string(&transaction_string),
+            transfer_id: None
         }
     }
 
     /** this method does the same thing as from_file() and operates like it, 
      but is for use with tsv files, while the former assumes json. */
-    pub fn from_tsv_file(f: &str) -> Result<Vec<Record>, String> {
-        match file_contents_from(f) {
-            Ok(content) => {
-                let lines: Vec<&str> = content.lines().map(|l| l).collect();
+    pub fn from_tsv_file<P: AsRef<Path>>(f: P) -> Result<Vec<Record>, Error> {
+        Record::from_tsv_file_with(f, &Layout::default())
+    }
 
-                let records: Vec<Record> = lines.iter().map(|line| Record::from_string(line)).collect();
+    /// load records from a tsv file whose columns are described by the given layout.
+    pub fn from_tsv_file_with<P: AsRef<Path>>(f: P, layout: &Layout) -> Result<Vec<Record>, Error> {
+        let file = open_file(&f)?;
 
-                Ok(records)
-            },
-            Err(error) => Err(format!("{}", error))
+        Record::from_tsv_reader_with(file, layout).map_err(|error| error.with_path(f))
+    }
+
+    /**
+     * load records from a tsv file, skipping rows that cannot be read.
+     * The rows that were skipped are returned alongside the records, so they can be reported.
+     */
+    pub fn from_tsv_file_lenient<P: AsRef<Path>>(f: P) -> Result<(Vec<Record>, Vec<Error>), Error> {
+        let file = open_file(&f)?;
+
+        Record::from_tsv_reader_lenient(file).map_err(|error| error.with_path(f))
+    }
+
+    /**
+     * this method does the same thing as from_reader(), but expects tab delimited data.
+     * If any row cannot be read, every bad row is reported together.
+     */
+    pub fn from_tsv_reader<R: Read>(reader: R) -> Result<Vec<Record>, Error> {
+        Record::from_tsv_reader_with(reader, &Layout::default())
+    }
+
+    /// this method does the same thing as from_tsv_reader(), but uses the given layout for the columns.
+    pub fn from_tsv_reader_with<R: Read>(reader: R, layout: &Layout) -> Result<Vec<Record>, Error> {
+        let (records, errors) = Record::from_tsv_reader_lenient_with(reader, layout)?;
+
+        if errors.is_empty() {
+            Ok(records)
+        } else {
+            Err(Error::Rows(errors))
         }
     }
 
-    /// presents a string version of the record.
-    pub fn to_string(&self) -> String {
-        format!("{}\t{}", self.id, self.transaction)
+    /// this method does the same thing as from_tsv_file_lenient(), but works with anything that can be read.
+    pub fn from_tsv_reader_lenient<R: Read>(reader: R) -> Result<(Vec<Record>, Vec<Error>), Error> {
+        Record::from_tsv_reader_lenient_with(reader, &Layout::default())
+    }
+
+    /**
+     * this method does the same thing as from_tsv_reader_lenient(), but uses the given layout for the columns.
+     * If the first row is a header, it is skipped. The columns it names are used instead of the layout's,
+     * unless the layout has its own columns and a header, in which case its columns are kept.
+     */
+    pub fn from_tsv_reader_lenient_with<R: Read>(reader: R, layout: &Layout) -> Result<(Vec<Record>, Vec<Error>), Error> {
+        let rows = BufReader::new(reader).lines().enumerate().map(|(index, line)| {
+            Ok((index + 1, line?.split('\t').map(String::from).collect()))
+        });
+
+        layout.records_from_rows(rows)
+    }
+
+    /// load records from a csv file, using the given format.
+    pub fn from_csv_file<P: AsRef<Path>>(f: P, format: &CsvFormat) -> Result<Vec<Record>, Error> {
+        let file = open_file(&f)?;
+
+        Record::from_csv_reader(file, format).map_err(|error| error.with_path(f))
+    }
+
+    /**
+     * load records from comma separated values, using the given format.
+     * If any row cannot be read, every bad row is reported together.
+     */
+    pub fn from_csv_reader<R: Read>(reader: R, format: &CsvFormat) -> Result<Vec<Record>, Error> {
+        let (records, errors) = Record::from_csv_reader_lenient(reader, format)?;
+
+        if errors.is_empty() {
+            Ok(records)
+        } else {
+            Err(Error::Rows(errors))
+        }
+    }
+
+    /// load records from comma separated values, skipping rows that cannot be read and returning them alongside the records.
+    pub fn from_csv_reader_lenient<R: Read>(mut reader: R, format: &CsvFormat) -> Result<(Vec<Record>, Vec<Error>), Error> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+
+        format.records_from_str(&content)
+    }
+
+    /**
+     * load records from an OFX or QFX statement file, like the ones offered by banks and credit unions.
+     * Ids are made from the transaction ids in the statement, so loading the same statement again gives the same records.
+     */
+    pub fn from_ofx_file<P: AsRef<Path>>(f: P) -> Result<Vec<Record>, Error> {
+        let file = open_file(&f)?;
+
+        Record::from_ofx_reader(file).map_err(|error| error.with_path(f))
+    }
+
+    /// this method does the same thing as from_ofx_file(), but works with anything that can be read.
+    pub fn from_ofx_reader<R: Read>(mut reader: R) -> Result<Vec<Record>, Error> {
+        let mut content: Vec<u8> = vec![];
+        reader.read_to_end(&mut content)?;
+
+        // OFX 1.x files are often not UTF-8, so avoid failing over an accented character in a payee name.
+        ofx::records_from_ofx(&String::from_utf8_lossy(&content))
+    }
+
+    /// load records from a QIF file, like the ones made by Quicken, GnuCash, and many banks.
+    pub fn from_qif_file<P: AsRef<Path>>(f: P) -> Result<Vec<Record>, Error> {
+        let file = open_file(&f)?;
+
+        Record::from_qif_reader(file).map_err(|error| error.with_path(f))
+    }
+
+    /// this method does the same thing as from_qif_file(), but works with anything that can be read.
+    pub fn from_qif_reader<R: Read>(mut reader: R) -> Result<Vec<Record>, Error> {
+        let mut content: Vec<u8> = vec![];
+        reader.read_to_end(&mut content)?;
+
+        // QIF files predate UTF-8, so avoid failing over an accented character in a payee name.
+        qif::records_from_qif(&String::from_utf8_lossy(&content))
     }
 }
 
 // apply trait to get item to be representable by string
 impl fmt::Display for Record {
+    /// presents a string version of the record.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\t{}", self.id, self.transaction)
+    }
+}
+
+// implement trait needed to parse a tab delimited row.
+impl FromStr for Record {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split('\t').collect();
+
+        Layout::default().record_from_fields(&fields)
+    }
+}
+
+impl TryFrom<&str> for Record {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
 
-        write!(f, "{}", self.to_string())
+impl Default for Record {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -122,13 +271,10 @@ impl PartialEq for Record {
     }
 }
 
-fn default_id() -> String {
+pub(crate) fn default_id() -> String {
     Uuid::new_v4().hyphenated().to_string()
 }
 
-fn file_contents_from(f: &str) -> Result<String, io::Error> {
-        let mut file_content = String::new();
-        File::open(f)?.read_to_string(&mut file_content)?;
-
-        Ok(file_content)
-}
\ No newline at end of file
+fn open_file<P: AsRef<Path>>(f: P) -> Result<File, Error> {
+    File::open(&f).map_err(|error| Error::from(error).with_path(f))
+}
@@ -0,0 +1,206 @@
+// import custom types, so that records can be kept and saved.
+use crate::{ error::Error, layout::Layout, record::Record, save_vec::Save };
+
+// import serde, so that a register is stored the same way as a list of records.
+use serde::{ Serialize, Deserialize };
+
+// import things needed to look up records, hand out slices, and write data.
+use std::{ collections::HashMap, io::Write, ops::Deref, path::Path, slice, vec };
+
+/**
+ * A check register, which holds records in date order and makes sure no two records share an id.
+ * Records on the same day are kept in the order they were added, and can be looked up by id without searching.
+ * # Example
+ * ```
+ * use bcheck::{ Record, Register };
+ *
+ * let mut register = Register::new();
+ * let record = Record::new();
+ *
+ * register.insert(record.clone()).unwrap();
+ *
+ * assert!(register.insert(record.clone()).is_err());
+ * assert_eq!(register.get(&record.id), Some(&record));
+ * ```
+ */
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Record>", into = "Vec<Record>")]
+pub struct Register {
+    records: Vec<Record>,
+    indices: HashMap<String, usize>
+}
+
+impl Register {
+    /// create an empty register.
+    pub fn new() -> Register {
+        Register::default()
+    }
+
+    /// load a register from a bcheck file, failing if two records share an id.
+    pub fn from_file<P: AsRef<Path>>(f: P) -> Result<Register, Error> {
+        let records = Record::from_file(&f)?;
+
+        Register::try_from(records).map_err(|error| error.with_path(f))
+    }
+
+    /// load a register from a tab delimited file, failing if two records share an id.
+    pub fn from_tsv_file<P: AsRef<Path>>(f: P) -> Result<Register, Error> {
+        let records = Record::from_tsv_file(&f)?;
+
+        Register::try_from(records).map_err(|error| error.with_path(f))
+    }
+
+    /// the record with the given id.
+    pub fn get(&self, id: &str) -> Option<&Record> {
+        self.indices.get(id).map(|index| &self.records[*index])
+    }
+
+    /// whether a record with the given id is in the register.
+    pub fn contains(&self, id: &str) -> bool {
+        self.indices.contains_key(id)
+    }
+
+    /// the records in date order.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// add a record, failing if a record with the same id is already in the register.
+    pub fn insert(&mut self, record: Record) -> Result<(), Error> {
+        if self.contains(&record.id) {
+            return Err(Error::DuplicateId(record.id))
+        }
+
+        self.place(record);
+
+        Ok(())
+    }
+
+    /**
+     * replace the record with the same id, returning the old one.
+     * The record is moved if its date changed, and an error is returned if there is nothing to replace.
+     */
+    pub fn update(&mut self, record: Record) -> Result<Record, Error> {
+        let Some(&index) = self.indices.get(&record.id) else {
+            return Err(Error::UnknownId(record.id))
+        };
+
+        if self.records[index].transaction.date == record.transaction.date {
+            return Ok(std::mem::replace(&mut self.records[index], record))
+        }
+
+        let old_record = self.take(index);
+        self.place(record);
+
+        Ok(old_record)
+    }
+
+    /// take the record with the given id out of the register.
+    pub fn remove(&mut self, id: &str) -> Option<Record> {
+        let index = *self.indices.get(id)?;
+
+        Some(self.take(index))
+    }
+
+    /// replace the record with the same id, or add it if there is none, returning the record that was replaced.
+    pub fn upsert(&mut self, record: Record) -> Option<Record> {
+        if self.contains(&record.id) {
+            self.update(record).ok()
+        } else {
+            self.place(record);
+            None
+        }
+    }
+
+    // put a record after every record on the same day or before, so that the order of same day records is kept.
+    fn place(&mut self, record: Record) {
+        let index = self.records.partition_point(|existing| existing.transaction.date <= record.transaction.date);
+
+        self.records.insert(index, record);
+        self.reindex(index);
+    }
+
+    // take the record at the given position out of the register.
+    fn take(&mut self, index: usize) -> Record {
+        let record = self.records.remove(index);
+
+        self.indices.remove(&record.id);
+        self.reindex(index);
+
+        record
+    }
+
+    // update the positions of every record from the given position onwards.
+    fn reindex(&mut self, start: usize) {
+        for (index, record) in self.records.iter().enumerate().skip(start) {
+            self.indices.insert(record.id.clone(), index);
+        }
+    }
+}
+
+impl Deref for Register {
+    type Target = [Record];
+
+    fn deref(&self) -> &Self::Target {
+        &self.records
+    }
+}
+
+// implement trait needed to create a register from records, which fails if two records share an id.
+impl TryFrom<Vec<Record>> for Register {
+    type Error = Error;
+
+    fn try_from(records: Vec<Record>) -> Result<Self, Self::Error> {
+        let mut register = Register::new();
+
+        // sort first, so that every record does not need to be moved along as records are added.
+        let mut records = records;
+        records.sort_by_key(|record| record.transaction.date);
+
+        for record in records {
+            if register.contains(&record.id) {
+                return Err(Error::DuplicateId(record.id))
+            }
+
+            register.indices.insert(record.id.clone(), register.records.len());
+            register.records.push(record);
+        }
+
+        Ok(register)
+    }
+}
+
+impl From<Register> for Vec<Record> {
+    fn from(register: Register) -> Self {
+        register.records
+    }
+}
+
+impl IntoIterator for Register {
+    type Item = Record;
+    type IntoIter = vec::IntoIter<Record>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.records.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Register {
+    type Item = &'a Record;
+    type IntoIter = slice::Iter<'a, Record>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.records.iter()
+    }
+}
+
+// add implementation of Save trait, so that a register saves the same way a vector of records does.
+impl Save for Register {
+    fn to_writer(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        self.records.to_writer(writer)
+    }
+
+    fn to_tsv_writer_with(&self, writer: &mut dyn Write, layout: &Layout) -> Result<(), Error> {
+        self.records.to_tsv_writer_with(writer, layout)
+    }
+}
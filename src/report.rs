@@ -0,0 +1,145 @@
+// import custom types, so that records can be added up.
+use crate::{ csv_format::CsvFormat, error::Error, money::Money, period::Period, record::Record, transaction_type::TransactionType };
+
+// import chrono, so that rows can say which days they cover.
+use chrono::NaiveDate;
+
+// import Serde, so that reports can be written as JSON.
+use serde::Serialize;
+
+// import things needed to group rows and write reports.
+use std::{ collections::BTreeMap, fmt, io::Write };
+
+/// the name used for transactions without a category.
+const UNCATEGORIZED: &str = "Uncategorized";
+
+/// Represents the totals for a single category over a single period.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ReportRow {
+    /// a short name for the period, such as 2021-07.
+    pub period: String,
+
+    /// the first day of the period.
+    pub start: NaiveDate,
+
+    /// the last day of the period.
+    pub end: NaiveDate,
+
+    /// the category, which is None for transactions without one.
+    pub category: Option<String>,
+
+    /// money that came in.
+    pub deposits: Money,
+
+    /// money that went out, as a positive amount.
+    pub withdrawals: Money,
+
+    /// deposits minus withdrawals.
+    pub net: Money
+}
+
+/**
+ * Adds up records by category and period, to show where money came from and where it went.
+ * Transactions without a category are kept together in their own row.
+ * # Example
+ * ```
+ * use bcheck::{ Period, Record, Report };
+ *
+ * let records = vec![Record::new()];
+ *
+ * print!("{}", Report::new(&records, Period::Month));
+ * ```
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    rows: Vec<ReportRow>
+}
+
+impl Report {
+    /// add up the given records. With a custom period, records outside of it are left out.
+    pub fn new(records: &[Record], period: Period) -> Report {
+        // uncategorized transactions go after the categories in each period.
+        let mut rows: BTreeMap<(NaiveDate, bool, Option<String>), ReportRow> = BTreeMap::new();
+
+        for record in records {
+            let Some((start, end)) = period.bounds(record.transaction.date.date_naive()) else {
+                continue
+            };
+
+            // split transactions count towards the category of each split.
+            for (category, amount) in record.transaction.category_amounts() {
+                let category = category.map(String::from);
+
+                let row = rows.entry((start, category.is_none(), category.clone())).or_insert_with(|| ReportRow {
+                    period: period.label(start),
+                    start,
+                    end,
+                    category,
+                    deposits: Money::ZERO,
+                    withdrawals: Money::ZERO,
+                    net: Money::ZERO
+                });
+
+                match record.transaction.transaction_type {
+                    TransactionType::Deposit => row.deposits = row.deposits.saturating_add(amount),
+                    TransactionType::Withdrawal => row.withdrawals = row.withdrawals.saturating_add(amount)
+                }
+
+                row.net = row.deposits.saturating_sub(row.withdrawals);
+            }
+        }
+
+        Report {
+            rows: rows.into_values().collect()
+        }
+    }
+
+    /// the rows of the report, in order of period and then category.
+    pub fn rows(&self) -> &[ReportRow] {
+        &self.rows
+    }
+
+    /// write the report as comma separated values, with a header row.
+    pub fn to_csv_writer<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        let format = CsvFormat::new();
+
+        write!(writer, "{}", format.row(&["period", "start", "end", "category", "deposits", "withdrawals", "net"].map(String::from)))?;
+
+        for row in &self.rows {
+            write!(writer, "{}", format.row(&[
+                row.period.clone(),
+                row.start.to_string(),
+                row.end.to_string(),
+                row.category.clone().unwrap_or_else(|| String::from(UNCATEGORIZED)),
+                row.deposits.to_string(),
+                row.withdrawals.to_string(),
+                row.net.to_string()
+            ]))?;
+        }
+
+        Ok(())
+    }
+
+    /// write the report as JSON, with uncategorized rows having a null category.
+    pub fn to_json_writer<W: Write>(&self, writer: W) -> Result<(), Error> {
+        serde_json::to_writer_pretty(writer, &self.rows)?;
+
+        Ok(())
+    }
+}
+
+// implement trait needed to display the report as a plain text table.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let category_width = self.rows.iter().map(|row| row.category.as_deref().unwrap_or(UNCATEGORIZED).chars().count()).chain([8]).max().unwrap_or(8);
+        let period_width = self.rows.iter().map(|row| row.period.chars().count()).chain([6]).max().unwrap_or(6);
+
+        writeln!(f, "{:<period_width$}  {:<category_width$}  {:>12}  {:>12}  {:>12}", "Period", "Category", "Deposits", "Withdrawals", "Net")?;
+
+        for row in &self.rows {
+            writeln!(f, "{:<period_width$}  {:<category_width$}  {:>12}  {:>12}  {:>12}", row.period, row.category.as_deref().unwrap_or(UNCATEGORIZED), row.deposits.to_string(), row.withdrawals.to_string(), row.net.to_string())?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+// import Serde, so that rollover policies can be stored with budgets.
+use serde::{ Serialize, Deserialize };
+
+/// Represents what happens to what is left of a budget at the end of a period.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rollover {
+    /// every period starts with just the budgeted amount.
+    #[default]
+    None,
+
+    /// money that was not spent is added to the next period, but overspending is forgotten.
+    Unspent,
+
+    /// money that was not spent is added to the next period, and overspending is taken out of it.
+    All
+}
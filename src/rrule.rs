@@ -0,0 +1,124 @@
+// import custom types, so that schedules can be turned into RRULE strings and back.
+use crate::{ error::Error, frequency::Frequency, schedule_end::ScheduleEnd };
+
+// import chrono, so that start days and end days can be used.
+use chrono::{ Datelike, NaiveDate, Weekday };
+
+/// the days Monday to Friday, as written in an RRULE.
+const BUSINESS_DAYS: &str = "MO,TU,WE,TH,FR";
+
+/**
+ * write a frequency and end as an RFC 5545 RRULE, such as FREQ=MONTHLY;BYMONTHDAY=1;COUNT=12.
+ * Months too short for the day are given their last day instead of being skipped, the same way schedules work.
+ */
+pub(crate) fn string_from_schedule(frequency: Frequency, end: ScheduleEnd, start: NaiveDate) -> String {
+    let mut rule = match frequency {
+        Frequency::Weekly => String::from("FREQ=WEEKLY"),
+        Frequency::Biweekly => String::from("FREQ=WEEKLY;INTERVAL=2"),
+        Frequency::MonthlyOnDay { day } if day <= 28 => format!("FREQ=MONTHLY;BYMONTHDAY={}", day.max(1)),
+        Frequency::MonthlyOnDay { day } => format!("FREQ=MONTHLY;BYMONTHDAY={};BYSETPOS=-1", month_days(day.min(31))),
+        Frequency::LastBusinessDay => format!("FREQ=MONTHLY;BYDAY={};BYSETPOS=-1", BUSINESS_DAYS),
+        Frequency::Yearly if start.month() == 2 && start.day() == 29 => String::from("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=28,29;BYSETPOS=-1"),
+        Frequency::Yearly => String::from("FREQ=YEARLY")
+    };
+
+    match end {
+        ScheduleEnd::Never => (),
+        ScheduleEnd::On(date) => rule.push_str(&format!(";UNTIL={}", date.format("%Y%m%d"))),
+        ScheduleEnd::After(count) => rule.push_str(&format!(";COUNT={}", count))
+    }
+
+    rule
+}
+
+/**
+ * read an RFC 5545 RRULE, such as the ones made by calendar apps, for a schedule starting on the given day.
+ * Only rules that match one of the frequencies schedules support can be read.
+ */
+pub(crate) fn schedule_from_string(s: &str, start: NaiveDate) -> Result<(Frequency, ScheduleEnd), Error> {
+    let invalid = || Error::Invalid { value: s.to_string(), expected: "an RRULE that repeats weekly, every other week, monthly on a day, on the last business day of each month, or yearly" };
+
+    let rule = s.trim();
+    let rule = rule.strip_prefix("RRULE:").or_else(|| rule.strip_prefix("rrule:")).unwrap_or(rule);
+
+    let mut frequency = None;
+    let mut interval = 1;
+    let mut by_day = None;
+    let mut by_month_day = None;
+    let mut by_month = None;
+    let mut by_set_position = None;
+    let mut end = ScheduleEnd::Never;
+
+    for part in rule.split(';').filter(|part| !part.is_empty()) {
+        let (name, value) = part.split_once('=').ok_or_else(invalid)?;
+        let value = value.to_uppercase();
+
+        match name.to_uppercase().as_str() {
+            "FREQ" => frequency = Some(value),
+            "INTERVAL" => interval = value.parse::<u32>().map_err(|_| invalid())?,
+            "BYDAY" => by_day = Some(value),
+            "BYMONTHDAY" => by_month_day = Some(value),
+            "BYMONTH" => by_month = Some(value.parse::<u32>().map_err(|_| invalid())?),
+            "BYSETPOS" => by_set_position = Some(value),
+            "COUNT" => end = ScheduleEnd::After(value.parse::<u32>().map_err(|_| invalid())?),
+            "UNTIL" => {
+                let date = value.get(..8).and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok()).ok_or_else(|| Error::Date(value.clone()))?;
+
+                end = ScheduleEnd::On(date)
+            },
+            "WKST" => (),
+            _ => return Err(invalid())
+        }
+    }
+
+    let is_last = by_set_position.as_deref() == Some("-1");
+
+    let frequency = match (frequency.as_deref(), interval) {
+        (Some("WEEKLY"), 1 | 2) => {
+            // the day of the week comes from the start, so any other day cannot be used.
+            if by_month_day.is_some() || by_month.is_some() || by_day.is_some_and(|day| day != weekday_code(start.weekday())) {
+                return Err(invalid())
+            }
+
+            if interval == 1 { Frequency::Weekly } else { Frequency::Biweekly }
+        },
+        (Some("MONTHLY"), 1) => match (by_day.as_deref(), by_month_day.as_deref()) {
+            (Some(BUSINESS_DAYS), None) if is_last => Frequency::LastBusinessDay,
+            (None, None) => Frequency::MonthlyOnDay { day: start.day() },
+            (None, Some("-1")) => Frequency::MonthlyOnDay { day: 31 },
+            (None, Some(days)) => match days.parse::<u32>() {
+                Ok(day @ 1..=31) if by_set_position.is_none() => Frequency::MonthlyOnDay { day },
+                _ => (29..=31).find(|day| is_last && days == month_days(*day)).map(|day| Frequency::MonthlyOnDay { day }).ok_or_else(invalid)?
+            },
+            _ => return Err(invalid())
+        },
+        (Some("YEARLY"), 1) => {
+            let is_leap_day = start.month() == 2 && start.day() == 29;
+
+            let is_start_day = match by_month_day.as_deref() {
+                None => true,
+                Some("28,29") => is_leap_day && is_last,
+                Some(day) => day.parse::<u32>().ok() == Some(start.day())
+            };
+
+            if by_day.is_some() || by_month.is_some_and(|month| month != start.month()) || !is_start_day {
+                return Err(invalid())
+            }
+
+            Frequency::Yearly
+        },
+        _ => return Err(invalid())
+    };
+
+    Ok((frequency, end))
+}
+
+// the days from the 28th to the given day, which are needed to say the last of them in each month.
+fn month_days(day: u32) -> String {
+    (28..=day).map(|day| day.to_string()).collect::<Vec<String>>().join(",")
+}
+
+// the two letter code used for a day of the week.
+fn weekday_code(weekday: Weekday) -> String {
+    weekday.to_string().to_uppercase()[..2].to_string()
+}
@@ -0,0 +1,78 @@
+// import custom type, so that balances can be worked out.
+use crate::{ money::Money, record::Record };
+
+// import chrono, so that balances can be given for a particular day.
+use chrono::NaiveDate;
+
+/**
+ * Works out balances over a register, the same way BCheckbook shows them.
+ * Records are put in date order, with records on the same day kept in the order they were given.
+ * # Example
+ * ```
+ * use bcheck::{ Money, Record, RunningBalance };
+ *
+ * let records = vec![Record::new()];
+ *
+ * for (record, balance) in RunningBalance::new(&records).with_opening_balance(Money::from_cents(10000)).iter() {
+ *     println!("{}\t{}", record.transaction.vendor, balance)
+ * }
+ * ```
+ */
+#[derive(Clone, Debug)]
+pub struct RunningBalance<'a> {
+    records: Vec<&'a Record>,
+    opening_balance: Money
+}
+
+impl<'a> RunningBalance<'a> {
+    /// create running balance over the given records, starting from zero.
+    pub fn new(records: &'a [Record]) -> RunningBalance<'a> {
+        let mut sorted_records: Vec<&Record> = records.iter().collect();
+        sorted_records.sort_by_key(|record| record.transaction.date);
+
+        RunningBalance {
+            records: sorted_records,
+            opening_balance: Money::ZERO
+        }
+    }
+
+    /// set the balance the account had before the first record.
+    pub fn with_opening_balance(mut self, opening_balance: Money) -> RunningBalance<'a> {
+        self.opening_balance = opening_balance;
+        self
+    }
+
+    /// the balance the account had before the first record.
+    pub fn opening_balance(&self) -> Money {
+        self.opening_balance
+    }
+
+    /// go through records in date order, along with the balance after each one.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a Record, Money)> + '_ {
+        self.records.iter().scan(self.opening_balance, |balance, record| {
+            *balance = balance.saturating_add(record.transaction.signed_amount());
+
+            Some((*record, *balance))
+        })
+    }
+
+    /// the balance after every record.
+    pub fn current(&self) -> Money {
+        self.total(|_| true)
+    }
+
+    /// the balance after only the records that have been reconciled, which should match what the bank says.
+    pub fn reconciled(&self) -> Money {
+        self.total(|record| record.transaction.is_reconciled)
+    }
+
+    /// the balance at the end of the given day.
+    pub fn as_of(&self, date: NaiveDate) -> Money {
+        self.total(|record| record.transaction.date.date_naive() <= date)
+    }
+
+    // add up the records that should be counted, saturating since amounts come from files that may not be trustworthy.
+    fn total<F>(&self, should_count: F) -> Money where F: Fn(&Record) -> bool {
+        self.records.iter().filter(|record| should_count(record)).fold(self.opening_balance, |balance, record| balance.saturating_add(record.transaction.signed_amount()))
+    }
+}
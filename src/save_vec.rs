@@ -1,37 +1,108 @@
 // import custom type for use in specifying particular vector
-use crate::Record;
+use crate::{ Record, layout::Layout };
 
 // import things needed for dealing with files, so saving can work.
-use std::{fs::File, io::{ Write, Error } };
+use std::{ fs::{ self, File }, io::{ self, BufWriter, ErrorKind, Write }, path::Path };
 
-// import serde_json, to save contents as JSON
-use serde_json;
+// import custom error, so that failures can be reported.
+use crate::error::Error;
 
-/// enables the ability to save data when implemented.
+/**
+ * enables the ability to save data when implemented.
+ * Every method has a default, so implementing the writer methods is enough to be able to save files,
+ * while types that only implement save() and save_tsv() keep working. The trait can be used as dyn Save.
+ */
 pub trait Save {
-    fn save(&self, path: &str) -> Result<(), Error>;
-    fn save_tsv(&self, path: &str) -> Result<(), Error>;
+    /// write data as JSON to anything that can be written to, such as a file, network stream, or buffer in memory.
+    fn to_writer(&self, _writer: &mut dyn Write) -> Result<(), Error> {
+        Err(unsupported("writing JSON"))
+    }
+
+    /// write data as tab delimited text to anything that can be written to, using the given layout for the columns.
+    fn to_tsv_writer_with(&self, _writer: &mut dyn Write, _layout: &Layout) -> Result<(), Error> {
+        Err(unsupported("writing tab delimited text"))
+    }
+
+    /// write data as tab delimited text to anything that can be written to.
+    fn to_tsv_writer(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        self.to_tsv_writer_with(writer, &Layout::default())
+    }
+
+    /// save data as JSON to the given path.
+    fn save(&self, path: &Path) -> Result<(), Error> {
+        write_file(path, |output| self.to_writer(output))
+    }
+
+    /// save data as tab delimited text to the given path.
+    fn save_tsv(&self, path: &Path) -> Result<(), Error> {
+        write_file(path, |output| self.to_tsv_writer(output))
+    }
+
+    /// save data as tab delimited text to the given path, using the given layout for the columns.
+    fn save_tsv_with(&self, path: &Path, layout: &Layout) -> Result<(), Error> {
+        write_file(path, |output| self.to_tsv_writer_with(output, layout))
+    }
 }
 
-// add implementation of Save trait to Vector of Records.
+// add implementation of Save trait to Vector of Records, which saves the same way a slice does.
 impl Save for Vec<Record> {
-    fn save(&self, path: &str) -> Result<(), Error> {
-        let mut output = File::create(path)?;
-        let json_string = serde_json::to_string_pretty(self)?;
+    fn to_writer(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        self.as_slice().to_writer(writer)
+    }
 
-        match write!(output, "{}", format!("{}", json_string)) {
-            Ok(()) => Ok(()),
-            Err(error) => Err(error)
-        }  
+    fn to_tsv_writer_with(&self, writer: &mut dyn Write, layout: &Layout) -> Result<(), Error> {
+        self.as_slice().to_tsv_writer_with(writer, layout)
     }
+}
 
-    fn save_tsv(&self, path: &str) -> Result<(), Error> {
-        let mut output = File::create(path)?;
-        let tsv_string: String = self.iter().map(|record| record.to_string() + "\r\n").collect();
+// add implementation of Save trait to slices of Records, so that part of a list can be saved.
+impl Save for [Record] {
+    fn to_writer(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        serde_json::to_writer_pretty(writer, self)?;
 
-        match write!(output, "{}", format!("{}", tsv_string)) {
-            Ok(()) => Ok(()),
-            Err(error) => Err(error)
-        } 
+        Ok(())
     }
-}
\ No newline at end of file
+
+    fn to_tsv_writer_with(&self, writer: &mut dyn Write, layout: &Layout) -> Result<(), Error> {
+        if layout.has_header() {
+            write!(writer, "{}\r\n", layout.header_fields().join("\t"))?;
+        }
+
+        for record in self {
+            write!(writer, "{}\r\n", layout.fields_from_record(record).join("\t"))?;
+        }
+
+        Ok(())
+    }
+}
+
+// the error given by a writer method that a type does not implement.
+fn unsupported(action: &str) -> Error {
+    Error::from(io::Error::new(ErrorKind::Unsupported, format!("{} is not supported by this type", action)))
+}
+
+/**
+ * hand a file off to be written, making sure errors mention the path.
+ * The data is written to a file next to the real one, which is only moved into place once it is complete,
+ * so that a failed write leaves the old file alone.
+ */
+pub(crate) fn write_file<P, F>(path: P, write: F) -> Result<(), Error> where P: AsRef<Path>, F: FnOnce(&mut BufWriter<File>) -> Result<(), Error> {
+    let path = path.as_ref();
+    let mut temporary_name = path.file_name().unwrap_or_default().to_os_string();
+    temporary_name.push(".tmp");
+    let temporary_path = path.with_file_name(temporary_name);
+
+    let result = File::create(&temporary_path).map_err(Error::from).and_then(|file| {
+        let mut output = BufWriter::new(file);
+        write(&mut output)?;
+        output.into_inner().map_err(|error| error.into_error())?.sync_all()?;
+
+        Ok(fs::rename(&temporary_path, path)?)
+    });
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary_path);
+    }
+
+    result.map_err(|error| error.with_path(path))
+}
@@ -0,0 +1,187 @@
+// import custom types, so that schedules can create records and be stored.
+use crate::{ companion, error::Error, frequency::Frequency, local_datetime_from_string::local_midnight, record::{ Record, default_id }, rrule, schedule_end::ScheduleEnd, transaction::Transaction };
+
+// import chrono, so that occurrences can be worked out.
+use chrono::NaiveDate;
+
+// import Serde, so that schedules can be stored as JSON.
+use serde::{ Serialize, Deserialize };
+
+// import uuid crate, so that each occurrence always gets the same record id.
+use uuid::Uuid;
+
+// import things needed to keep track of posted occurrences and find schedule files.
+use std::{ collections::BTreeSet, path::{ Path, PathBuf } };
+
+/**
+ * Represents a transaction that repeats, such as rent, pay, or a utility bill.
+ * Schedules are not part of the bcheck format, so they are kept in a JSON file next to the bcheck file.
+ * # Example
+ * ```
+ * use bcheck::{ Frequency, Schedule, ScheduleEnd, Transaction, TransactionType };
+ * use chrono::NaiveDate;
+ *
+ * let rent = Transaction::from(None, None, Some("Housing"), "Landlord", "Rent", 950.0, TransactionType::Withdrawal, false).unwrap();
+ * let mut schedule = Schedule::new(rent, NaiveDate::from_ymd_opt(2021, 7, 1).unwrap(), Frequency::MonthlyOnDay { day: 1 }).with_end(ScheduleEnd::After(12));
+ *
+ * // create the records that are due, and remember that they were posted.
+ * let records = schedule.post_through(NaiveDate::from_ymd_opt(2021, 9, 15).unwrap());
+ *
+ * assert_eq!(records.len(), 3);
+ * ```
+ */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    /// schedule identifier, which is used to make the ids of the records it creates.
+    #[serde(default = "default_id")]
+    pub id: String,
+
+    /// the transaction to create each time. Its date is replaced with the date of each occurrence.
+    pub transaction: Transaction,
+
+    /// the day the schedule starts.
+    pub start: NaiveDate,
+
+    /// how often the transaction happens.
+    pub frequency: Frequency,
+
+    /// when the transaction stops happening.
+    #[serde(default)]
+    pub end: ScheduleEnd,
+
+    /// the days of the occurrences that have already been added to a register.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub posted: BTreeSet<NaiveDate>
+}
+
+impl Schedule {
+    /// create a schedule that keeps going, starting on the given day. The date of the transaction is set to the start.
+    pub fn new(transaction: Transaction, start: NaiveDate, frequency: Frequency) -> Schedule {
+        Schedule {
+            id: default_id(),
+            transaction: Transaction {
+                date: local_midnight(start),
+                ..transaction
+            },
+            start,
+            frequency,
+            end: ScheduleEnd::Never,
+            posted: BTreeSet::new()
+        }
+    }
+
+    /**
+     * create a schedule from an RFC 5545 RRULE, such as FREQ=MONTHLY;BYMONTHDAY=1;COUNT=12, starting on the given day.
+     * An error is returned if the rule does not match one of the supported frequencies.
+     */
+    pub fn from_rrule(transaction: Transaction, start: NaiveDate, rrule: &str) -> Result<Schedule, Error> {
+        let (frequency, end) = rrule::schedule_from_string(rrule, start)?;
+
+        Ok(Schedule::new(transaction, start, frequency).with_end(end))
+    }
+
+    /// set when the transaction stops happening.
+    pub fn with_end(mut self, end: ScheduleEnd) -> Schedule {
+        self.end = end;
+        self
+    }
+
+    /**
+     * the schedule as an RFC 5545 RRULE, which calendar apps can use with first_occurrence() as DTSTART.
+     * DTSTART always counts as an occurrence, so the start cannot be used when it does not fall on the day of the rule.
+     */
+    pub fn rrule(&self) -> String {
+        rrule::string_from_schedule(self.frequency, self.end, self.start)
+    }
+
+    /// the day of the first occurrence, which can be after the start, such as a monthly schedule on the 15th starting on the 3rd.
+    pub fn first_occurrence(&self) -> Option<NaiveDate> {
+        self.occurrences().next()
+    }
+
+    /// the days the transaction happens on, from the start, in order. This goes on forever if the schedule does not end.
+    pub fn occurrences(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        let limit = match self.end {
+            ScheduleEnd::After(count) => count as usize,
+            _ => usize::MAX
+        };
+
+        (0..=u32::MAX)
+            .map_while(|index| self.frequency.candidate(self.start, index))
+            .filter(|date| *date >= self.start)
+            .take_while(|date| !matches!(self.end, ScheduleEnd::On(end) if *date > end))
+            .take(limit)
+    }
+
+    /// the days the transaction happens on from start to end, including both days.
+    pub fn occurrences_between(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        self.occurrences().take_while(|date| *date <= end).filter(|date| *date >= start).collect()
+    }
+
+    /// whether the occurrence on the given day has been added to a register.
+    pub fn is_posted(&self, date: NaiveDate) -> bool {
+        self.posted.contains(&date)
+    }
+
+    /// remember that the occurrence on the given day has been added to a register.
+    pub fn mark_posted(&mut self, date: NaiveDate) {
+        self.posted.insert(date);
+    }
+
+    /**
+     * create records for the occurrences from start to end that have not been posted yet.
+     * Each occurrence always gets the same record id, so adding one to a register twice is caught.
+     */
+    pub fn records_between(&self, start: NaiveDate, end: NaiveDate) -> Vec<Record> {
+        self.occurrences_between(start, end).into_iter().filter(|date| !self.is_posted(*date)).map(|date| self.record(date)).collect()
+    }
+
+    /// create records for every occurrence up to the given day that has not been posted yet, and mark them as posted.
+    pub fn post_through(&mut self, date: NaiveDate) -> Vec<Record> {
+        let records = self.records_between(self.start, date);
+
+        for record in &records {
+            self.mark_posted(record.transaction.date.date_naive());
+        }
+
+        records
+    }
+
+    /// the record for the occurrence on the given day.
+    pub fn record(&self, date: NaiveDate) -> Record {
+        let mut transaction = self.transaction.clone();
+        transaction.date = local_midnight(date);
+        transaction.is_reconciled = false;
+
+        Record {
+            id: Uuid::new_v5(&Uuid::NAMESPACE_URL, format!("schedule:{}:{}", self.id, date).as_bytes()).hyphenated().to_string(),
+            transaction,
+            transfer_id: None
+        }
+    }
+
+    /// the path of the file that holds the schedules for the given bcheck file, such as example.schedules.json for example.bcheck.
+    pub fn companion_path<P: AsRef<Path>>(bcheck_path: P) -> PathBuf {
+        companion::companion_path(bcheck_path, "schedules")
+    }
+
+    /// load schedules from the given file.
+    pub fn from_file<P: AsRef<Path>>(f: P) -> Result<Vec<Schedule>, Error> {
+        companion::load(f)
+    }
+
+    /// load the schedules kept next to the given bcheck file, which gives no schedules if there is no file yet.
+    pub fn from_companion_file<P: AsRef<Path>>(bcheck_path: P) -> Result<Vec<Schedule>, Error> {
+        companion::load_or_default(Schedule::companion_path(bcheck_path))
+    }
+
+    /// save schedules to the given file.
+    pub fn save_file<P: AsRef<Path>>(schedules: &[Schedule], f: P) -> Result<(), Error> {
+        companion::save(f, schedules)
+    }
+
+    /// save schedules next to the given bcheck file.
+    pub fn save_companion_file<P: AsRef<Path>>(schedules: &[Schedule], bcheck_path: P) -> Result<(), Error> {
+        Schedule::save_file(schedules, Schedule::companion_path(bcheck_path))
+    }
+}
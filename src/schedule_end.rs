@@ -0,0 +1,20 @@
+// import chrono, so that schedules can end on a particular day.
+use chrono::NaiveDate;
+
+// import Serde, so that endings can be stored with schedules.
+use serde::{ Serialize, Deserialize };
+
+/// Represents when a scheduled transaction stops happening.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum ScheduleEnd {
+    /// the transaction keeps happening.
+    #[default]
+    Never,
+
+    /// the transaction stops after the given day, which can be the day of the last occurrence.
+    On(NaiveDate),
+
+    /// the transaction happens the given number of times.
+    After(u32)
+}
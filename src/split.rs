@@ -0,0 +1,40 @@
+// import custom type, so that split amounts are exact.
+use crate::money::Money;
+
+// import Serde, so that splits can be stored with transactions.
+use serde::{ Serialize, Deserialize };
+
+/**
+ * Represents part of a transaction that belongs to its own category, such as the household items on a grocery receipt.
+ * The amount is in the same direction as the transaction, so it is positive for both deposits and withdrawals.
+ */
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Split {
+    /// the category of this part, which is None for uncategorized parts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+
+    /// the amount of this part.
+    pub amount: Money,
+
+    /// a note about this part.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub memo: String
+}
+
+impl Split {
+    /// create a split with the given category and amount.
+    pub fn new(category: Option<&str>, amount: Money) -> Split {
+        Split {
+            category: category.map(String::from),
+            amount,
+            memo: String::new()
+        }
+    }
+
+    /// set a note about this part.
+    pub fn with_memo(mut self, memo: &str) -> Split {
+        self.memo = String::from(memo);
+        self
+    }
+}
@@ -0,0 +1,436 @@
+// import custom types, so that records can be kept in a database.
+use crate::{ error::Error, filter::Filter, format::Format, local_datetime_from_string::local_midnight, money::Money, record::Record, register::Register, save_vec::Save, split::Split, transaction::Transaction, transaction_type::TransactionType };
+
+// import rusqlite, so that records can be stored in SQLite.
+use rusqlite::{ Connection, OptionalExtension, Row, ToSql, params, params_from_iter };
+
+// import chrono, so that dates can be stored as text.
+use chrono::NaiveDate;
+
+// import things needed to group splits and deal with paths.
+use std::{ collections::HashMap, path::Path };
+
+/// the version of the database layout, kept in SQLite's user_version.
+const VERSION: i32 = 1;
+
+/**
+ * the layout of the database. Amounts are stored in cents and dates as YYYY-MM-DD, and sequence keeps records on the same day in the order they were added.
+ * Vendors are also kept lowercased the same way filters compare them, with a trigram index so that they can be searched anywhere in the name.
+ */
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS records (
+        id TEXT PRIMARY KEY NOT NULL,
+        date TEXT NOT NULL,
+        sequence INTEGER NOT NULL UNIQUE,
+        check_number INTEGER,
+        category TEXT,
+        vendor TEXT NOT NULL,
+        vendor_key TEXT NOT NULL,
+        memo TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        type TEXT NOT NULL,
+        is_reconciled INTEGER NOT NULL,
+        transfer_id TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS splits (
+        record_id TEXT NOT NULL REFERENCES records (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        category TEXT,
+        amount INTEGER NOT NULL,
+        memo TEXT NOT NULL,
+        PRIMARY KEY (record_id, position)
+    );
+
+    CREATE INDEX IF NOT EXISTS records_by_date ON records (date, sequence);
+    CREATE INDEX IF NOT EXISTS records_by_category ON records (category COLLATE NOCASE);
+    CREATE INDEX IF NOT EXISTS splits_by_category ON splits (category COLLATE NOCASE);
+
+    CREATE VIRTUAL TABLE IF NOT EXISTS vendors USING fts5 (vendor_key, content = 'records', content_rowid = 'sequence', tokenize = 'trigram case_sensitive 1');
+
+    CREATE TRIGGER IF NOT EXISTS records_vendor_insert AFTER INSERT ON records BEGIN
+        INSERT INTO vendors (rowid, vendor_key) VALUES (new.sequence, new.vendor_key);
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS records_vendor_delete AFTER DELETE ON records BEGIN
+        INSERT INTO vendors (vendors, rowid, vendor_key) VALUES ('delete', old.sequence, old.vendor_key);
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS records_vendor_update AFTER UPDATE ON records BEGIN
+        INSERT INTO vendors (vendors, rowid, vendor_key) VALUES ('delete', old.sequence, old.vendor_key);
+        INSERT INTO vendors (rowid, vendor_key) VALUES (new.sequence, new.vendor_key);
+    END;
+";
+
+/// the columns read for each record, in the order record_from_row expects them.
+const COLUMNS: &str = "records.id, records.date, records.check_number, records.category, records.vendor, records.memo, records.amount, records.type, records.is_reconciled, records.transfer_id";
+
+/**
+ * Keeps records in a SQLite database, so that a single change does not mean writing out every record again.
+ * Records are kept in the same order as a register, and can be imported from and exported to bcheck and TSV files.
+ * Each change is made in a database transaction, and several changes can be grouped with transaction().
+ * # Example
+ * ```
+ * use bcheck::{ Filter, Record, SqliteStore };
+ *
+ * let mut store = SqliteStore::open_in_memory().unwrap();
+ * let record = Record::new();
+ *
+ * store.insert(record.clone()).unwrap();
+ *
+ * assert_eq!(store.get(&record.id).unwrap(), Some(record));
+ * assert_eq!(store.query(&Filter::new().with_reconciled(false)).unwrap().len(), 1);
+ * ```
+ */
+#[derive(Debug)]
+pub struct SqliteStore {
+    connection: Connection
+}
+
+impl SqliteStore {
+    /// open the database at the given path, creating it if it does not exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<SqliteStore, Error> {
+        SqliteStore::from_connection(Connection::open(path)?)
+    }
+
+    /// open a database that is only kept in memory, which is handy for tests.
+    pub fn open_in_memory() -> Result<SqliteStore, Error> {
+        SqliteStore::from_connection(Connection::open_in_memory()?)
+    }
+
+    // set up the tables, failing if the database was made by a newer version.
+    fn from_connection(connection: Connection) -> Result<SqliteStore, Error> {
+        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+
+        let version: i32 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        if version > VERSION {
+            return Err(Error::Invalid { value: version.to_string(), expected: "a database version this library knows about" })
+        }
+
+        connection.execute_batch(SCHEMA)?;
+        connection.execute_batch(&format!("PRAGMA user_version = {};", VERSION))?;
+
+        Ok(SqliteStore { connection })
+    }
+
+    /**
+     * make several changes at once, so that either all of them are kept or, if an error is returned, none of them are.
+     * Transactions can be nested, with each one being undone on its own.
+     */
+    pub fn transaction<T, F>(&mut self, change: F) -> Result<T, Error> where F: FnOnce(&mut SqliteStore) -> Result<T, Error> {
+        self.connection.execute_batch("SAVEPOINT bcheck")?;
+
+        let result = change(self);
+
+        match result {
+            Ok(_) => self.connection.execute_batch("RELEASE bcheck")?,
+            Err(_) => self.connection.execute_batch("ROLLBACK TO bcheck; RELEASE bcheck")?
+        }
+
+        result
+    }
+
+    /// the number of records.
+    pub fn len(&self) -> Result<usize, Error> {
+        let count: i64 = self.connection.query_row("SELECT COUNT(*) FROM records", [], |row| row.get(0))?;
+
+        Ok(count as usize)
+    }
+
+    /// whether there are no records.
+    pub fn is_empty(&self) -> Result<bool, Error> {
+        Ok(self.len()? == 0)
+    }
+
+    /// whether a record with the given id is stored.
+    pub fn contains(&self, id: &str) -> Result<bool, Error> {
+        Ok(self.connection.query_row("SELECT 1 FROM records WHERE id = ?1", [id], |_| Ok(())).optional()?.is_some())
+    }
+
+    /// the record with the given id.
+    pub fn get(&self, id: &str) -> Result<Option<Record>, Error> {
+        Ok(self.select("records.id = ?", vec![Box::new(id.to_string())])?.pop())
+    }
+
+    /// every record, in date order.
+    pub fn records(&self) -> Result<Vec<Record>, Error> {
+        self.select("1", vec![])
+    }
+
+    /// every record, as a register.
+    pub fn load(&self) -> Result<Register, Error> {
+        Register::try_from(self.records()?)
+    }
+
+    /**
+     * the records that match the given filter, in date order.
+     * Every part of the filter is checked in the database. Dates, categories, and vendors are looked up by index, with vendors matching anywhere in the name.
+     */
+    pub fn query(&self, filter: &Filter) -> Result<Vec<Record>, Error> {
+        let mut conditions: Vec<String> = vec![];
+        let mut values: Vec<Box<dyn ToSql>> = vec![];
+
+        // each condition refers to its value as $, which becomes the number of the value once it is added.
+        let mut add = |condition: &str, value: Box<dyn ToSql>| {
+            values.push(value);
+            conditions.push(condition.replace('$', &format!("?{}", values.len())));
+        };
+
+        if let Some(from) = filter.from {
+            add("records.date >= $", Box::new(date_string(from)));
+        }
+
+        if let Some(to) = filter.to {
+            add("records.date <= $", Box::new(date_string(to)));
+        }
+
+        // split transactions only count towards the categories of their splits.
+        if let Some(category) = &filter.category {
+            add("((records.category = $ COLLATE NOCASE AND NOT EXISTS (SELECT 1 FROM splits AS s WHERE s.record_id = records.id)) OR records.id IN (SELECT s.record_id FROM splits AS s WHERE s.category = $ COLLATE NOCASE))", Box::new(category.clone()));
+        }
+
+        // the trigram index can only find vendors by three characters or more, so shorter ones are checked on each record.
+        if let Some(vendor) = &filter.vendor {
+            let vendor = vendor.to_lowercase();
+
+            if vendor.chars().count() < 3 {
+                add("instr(records.vendor_key, $) > 0", Box::new(vendor));
+            } else {
+                add("records.sequence IN (SELECT rowid FROM vendors WHERE vendors MATCH $)", Box::new(format!("\"{}\"", vendor.replace('"', "\"\""))));
+            }
+        }
+
+        if let Some(transaction_type) = &filter.transaction_type {
+            add("records.type = $", Box::new(type_string(transaction_type)));
+        }
+
+        if let Some(reconciled) = filter.reconciled {
+            add("records.is_reconciled = $", Box::new(reconciled));
+        }
+
+        if let Some(min_amount) = filter.min_amount {
+            add("records.amount >= $", Box::new(min_amount.cents()));
+        }
+
+        if let Some(max_amount) = filter.max_amount {
+            add("records.amount <= $", Box::new(max_amount.cents()));
+        }
+
+        if conditions.is_empty() {
+            self.records()
+        } else {
+            self.select(&conditions.join(" AND "), values)
+        }
+    }
+
+    /// add a record, failing if a record with the same id is already stored.
+    pub fn insert(&mut self, record: Record) -> Result<(), Error> {
+        self.transaction(|store| {
+            if store.contains(&record.id)? {
+                return Err(Error::DuplicateId(record.id))
+            }
+
+            store.write(&record, None)
+        })
+    }
+
+    /**
+     * replace the record with the same id, returning the old one.
+     * The record is moved after the other records on its day if its date changed, the same way a register does.
+     */
+    pub fn update(&mut self, record: Record) -> Result<Record, Error> {
+        self.transaction(|store| {
+            let old_record = store.get(&record.id)?.ok_or_else(|| Error::UnknownId(record.id.clone()))?;
+            let sequence: Option<i64> = if old_record.transaction.date.date_naive() == record.transaction.date.date_naive() {
+                store.connection.query_row("SELECT sequence FROM records WHERE id = ?1", [&record.id], |row| row.get(0))?
+            } else {
+                None
+            };
+
+            store.connection.execute("DELETE FROM records WHERE id = ?1", [&record.id])?;
+            store.write(&record, sequence)?;
+
+            Ok(old_record)
+        })
+    }
+
+    /// take the record with the given id out of the store.
+    pub fn remove(&mut self, id: &str) -> Result<Option<Record>, Error> {
+        self.transaction(|store| {
+            let record = store.get(id)?;
+
+            store.connection.execute("DELETE FROM records WHERE id = ?1", [id])?;
+
+            Ok(record)
+        })
+    }
+
+    /// replace the record with the same id, or add it if there is none, returning the record that was replaced.
+    pub fn upsert(&mut self, record: Record) -> Result<Option<Record>, Error> {
+        if self.contains(&record.id)? {
+            self.update(record).map(Some)
+        } else {
+            self.insert(record).map(|_| None)
+        }
+    }
+
+    /// replace every record with the given ones, which is how a whole register is saved.
+    pub fn save(&mut self, records: &[Record]) -> Result<(), Error> {
+        self.transaction(|store| {
+            store.connection.execute("DELETE FROM records", [])?;
+            store.import(records).map(|_| ())
+        })
+    }
+
+    /// add the given records, failing without adding any of them if one of their ids is already stored.
+    pub fn import(&mut self, records: &[Record]) -> Result<usize, Error> {
+        self.transaction(|store| {
+            // records are added in date order, so that records on the same day keep their order.
+            let mut sorted_records: Vec<&Record> = records.iter().collect();
+            sorted_records.sort_by_key(|record| record.transaction.date.date_naive());
+
+            for record in &sorted_records {
+                if store.contains(&record.id)? {
+                    return Err(Error::DuplicateId(record.id.clone()))
+                }
+
+                store.write(record, None)?;
+            }
+
+            Ok(sorted_records.len())
+        })
+    }
+
+    /// add the records in a bcheck file, or a TSV file if the path ends in tsv, returning how many were added.
+    pub fn import_file<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, Error> {
+        let records = match Format::from_path(&path) {
+            Some(Format::Tsv) => Record::from_tsv_file(path)?,
+            _ => Record::from_file(path)?
+        };
+
+        self.import(&records)
+    }
+
+    /// write every record to a bcheck file, or a TSV file if the path ends in tsv.
+    pub fn export_file<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let records = self.records()?;
+
+        match Format::from_path(&path) {
+            Some(Format::Tsv) => records.save_tsv(path.as_ref()),
+            _ => records.save(path.as_ref())
+        }
+    }
+
+    /// the balance after every record, starting from zero.
+    pub fn current_balance(&self) -> Result<Money, Error> {
+        self.total("1")
+    }
+
+    /// the balance after every reconciled record, starting from zero.
+    pub fn reconciled_balance(&self) -> Result<Money, Error> {
+        self.total("is_reconciled = 1")
+    }
+
+    // add up the amounts of the records that match the given condition.
+    fn total(&self, condition: &str) -> Result<Money, Error> {
+        let cents: i64 = self.connection.query_row(&format!("SELECT IFNULL(SUM(CASE type WHEN 'deposit' THEN amount ELSE -amount END), 0) FROM records WHERE {}", condition), [], |row| row.get(0))?;
+
+        Ok(Money::from_cents(cents))
+    }
+
+    // store a record, putting it after every record already stored unless a place is given.
+    fn write(&self, record: &Record, sequence: Option<i64>) -> Result<(), Error> {
+        let transaction = &record.transaction;
+        let sequence = match sequence {
+            Some(sequence) => sequence,
+            None => self.connection.query_row("SELECT IFNULL(MAX(sequence), 0) + 1 FROM records", [], |row| row.get(0))?
+        };
+
+        self.connection.execute(
+            "INSERT INTO records (id, date, sequence, check_number, category, vendor, vendor_key, memo, amount, type, is_reconciled, transfer_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                record.id,
+                date_string(transaction.date.date_naive()),
+                sequence,
+                transaction.check_number,
+                transaction.category,
+                transaction.vendor,
+                transaction.vendor.to_lowercase(),
+                transaction.memo,
+                transaction.amount.cents(),
+                type_string(&transaction.transaction_type),
+                transaction.is_reconciled,
+                record.transfer_id
+            ]
+        )?;
+
+        for (position, split) in transaction.splits.iter().enumerate() {
+            self.connection.execute(
+                "INSERT INTO splits (record_id, position, category, amount, memo) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![record.id, position as i64, split.category, split.amount.cents(), split.memo]
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // read the records that match the given condition, along with their splits, in date order.
+    fn select(&self, condition: &str, values: Vec<Box<dyn ToSql>>) -> Result<Vec<Record>, Error> {
+        let mut statement = self.connection.prepare(&format!("SELECT {} FROM records WHERE {} ORDER BY records.date, records.sequence", COLUMNS, condition))?;
+        let mut records = statement.query_map(params_from_iter(values.iter()), record_from_row)?.collect::<Result<Vec<Record>, rusqlite::Error>>()?;
+
+        let mut statement = self.connection.prepare(&format!("SELECT splits.record_id, splits.category, splits.amount, splits.memo FROM splits JOIN records ON records.id = splits.record_id WHERE {} ORDER BY splits.record_id, splits.position", condition))?;
+        let mut splits: HashMap<String, Vec<Split>> = HashMap::new();
+
+        for row in statement.query_map(params_from_iter(values.iter()), |row| Ok((row.get::<_, String>(0)?, Split {
+            category: row.get(1)?,
+            amount: Money::from_cents(row.get(2)?),
+            memo: row.get(3)?
+        })))? {
+            let (record_id, split) = row?;
+            splits.entry(record_id).or_default().push(split);
+        }
+
+        for record in &mut records {
+            record.transaction.splits = splits.remove(&record.id).unwrap_or_default();
+        }
+
+        Ok(records)
+    }
+}
+
+// turn a row with the columns in COLUMNS into a record, without its splits.
+fn record_from_row(row: &Row) -> Result<Record, rusqlite::Error> {
+    let date: String = row.get(1)?;
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|error| rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(error)))?;
+
+    let transaction_type: String = row.get(7)?;
+
+    Ok(Record {
+        id: row.get(0)?,
+        transaction: Transaction {
+            date: local_midnight(date),
+            check_number: row.get(2)?,
+            category: row.get(3)?,
+            vendor: row.get(4)?,
+            memo: row.get(5)?,
+            amount: Money::from_cents(row.get(6)?),
+            transaction_type: transaction_type.parse::<TransactionType>().map_err(|error| rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, Box::new(error)))?,
+            is_reconciled: row.get(8)?,
+            splits: vec![]
+        },
+        transfer_id: row.get(9)?
+    })
+}
+
+fn date_string(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn type_string(transaction_type: &TransactionType) -> &'static str {
+    match transaction_type {
+        TransactionType::Deposit => "deposit",
+        TransactionType::Withdrawal => "withdrawal"
+    }
+}
@@ -6,17 +6,26 @@ use crate::transaction_type::TransactionType;
 
 use crate::LocalDateTimeStringExt;
 
+// import custom error, so that failures can be reported.
+use crate::error::Error;
+
+// import layout, so that tab delimited rows can be parsed.
+use crate::layout::Layout;
+
 // import chrono crate, so that ate can be included in seriallization and properly set.
 use chrono::prelude::*;
 
-// import OrderedFloat, so that transactions can be compared by amount
-use ordered_float::OrderedFloat;
+// import Money, so that amounts are exact and transactions can be compared by amount
+use crate::money::Money;
+
+// import Split, so that transactions can be spread across categories.
+use crate::split::Split;
 
 // import to use regex verification
 use regex::Regex;
 
-// import to allow formatting as string.
-use std::fmt;
+// import to allow formatting as string and parsing from strings.
+use std::{ fmt, str::FromStr };
 
 /// Represent a transaction made.
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialOrd, Ord)]
@@ -31,12 +40,15 @@ pub struct Transaction {
     pub vendor: String,
     #[serde(default = "String::new", skip_serializing_if = "String::is_empty")]
     pub memo: String,
-    #[serde(default = "default_float")]
-    pub amount: OrderedFloat<f64>,
+    #[serde(default)]
+    pub amount: Money,
     #[serde(rename = "type")]
     pub transaction_type: TransactionType,
     #[serde(default = "default_reconciled", skip_serializing_if = "is_default_reconciled")]
-    pub is_reconciled: bool
+    pub is_reconciled: bool,
+    /// parts of the transaction that belong to different categories. These are only written when present, so BCheckbook still sees the category and total amount.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub splits: Vec<Split>
 }
 
 impl Transaction {
@@ -48,59 +60,56 @@ impl Transaction {
             category: None,
             vendor: String::new(),
             memo: String::new(),
-            amount: default_float(),
+            amount: Money::ZERO,
             transaction_type: TransactionType::Withdrawal,
-            is_reconciled: default_reconciled()
-
+            is_reconciled: default_reconciled(),
+            splits: vec![]
         }
     }
 
     /**
      * Create a transaction object with given values.
      * This function will throw an Error if a given date string is not in the proper format, which is "yyyy-mm-dd" or "yyyy-m-d".
+     * The amount is rounded to the nearest cent, and an Error is thrown if it is not a finite number.
      * # Example
-     * ```let transaction = Transaction::from(None, Some(1260), Some("Opening Balance"), "Sam Hill Credit Union", "Open Account", 500 as f64, TransactionType::Deposit, false);```
+     * ```let transaction = Transaction::from(None, Some(1260), Some("Opening Balance"), "Sam Hill Credit Union", "Open Account", 500.0, TransactionType::Deposit, false);```
      */
-    pub fn from(date: Option<&str>, check_number: Option<u32>, category: Option<&str>, vendor: &str, memo: &str, amount: f64, transaction_type: TransactionType, is_reconciled: bool) -> Result<Transaction, String> {
-        if let Some(date_string) = date {
-            match date_string.local_datetime() {
-                Ok(date_time) => Ok(Transaction {
-                    date: date_time,
-                    check_number,
-                    category: if let Some(category) = category {
-                        Some(String::from(category))
-                    } else {
-                        None
-                    },
-                    vendor: String::from(vendor),
-                    memo: String::from(memo),
-                    amount: OrderedFloat(amount),
-                    transaction_type,
-                    is_reconciled
-                }),
-                Err(error) => Err(error)
-            }
-        } else {
-            Ok(Transaction {
-                date: Local::now(),
-                check_number,
-                category: if let Some(category) = category {
-                    Some(String::from(category))
-                } else {
-                    None
-                },
-                vendor: String::from(vendor),
-                memo: String::from(memo),
-                amount: OrderedFloat(amount),
-                transaction_type,
-                is_reconciled
-            })
+    #[allow(clippy::too_many_arguments)]
+    pub fn from(date: Option<&str>, check_number: Option<u32>, category: Option<&str>, vendor: &str, memo: &str, amount: f64, transaction_type: TransactionType, is_reconciled: bool) -> Result<Transaction, Error> {
+        Ok(Transaction {
+            date: match date {
+                Some(date_string) => date_string.local_datetime()?,
+                None => Local::now()
+            },
+            check_number,
+            category: category.map(String::from),
+            vendor: String::from(vendor),
+            memo: String::from(memo),
+            amount: Money::from_f64(amount).ok_or_else(|| Error::Invalid { value: amount.to_string(), expected: "an amount that fits in a whole number of cents" })?,
+            transaction_type,
+            is_reconciled,
+            splits: vec![]
+        })
+    }
+
+    /// the amount of the transaction with its effect on a balance, so deposits are positive and withdrawals are negative.
+    pub fn signed_amount(&self) -> Money {
+        match self.transaction_type {
+            TransactionType::Deposit => self.amount,
+            TransactionType::Withdrawal => Money::ZERO.saturating_sub(self.amount)
         }
     }
 
-    /// create a transaction directly from a string.
+    /**
+     * create a transaction directly from a string.
+     * This is lenient, so fields that cannot be read fall back to default values.
+     * If problems need to be known about, use parse() or try_from() instead.
+     */
     pub fn from_string(s: &str) -> Transaction {
-        let components: Vec<&str> = s.split('\t').collect();
+        let mut components: Vec<&str> = s.split('\t').collect();
+
+        // pad out short rows, so that missing columns are treated as empty.
+        components.resize(components.len().max(8), "");
 
         Transaction {
             date: if let Ok(transaction_date) = components[0].to_string().local_datetime() {
@@ -111,11 +120,7 @@ impl Transaction {
             check_number: if components[1].to_string().is_empty() {
                 None
             } else {
-                if let Ok(value) = components[1].parse::<u32>() {
-                    Some(value)
-                } else {
-                    None
-                }
+                components[1].parse::<u32>().ok()
             },
             category: if components[3].to_string().is_empty() {
                 None
@@ -125,21 +130,65 @@ impl Transaction {
             vendor: components[4].to_string(),
             memo: components[5].to_string(),
             amount: if components[6].to_string().is_empty() {
-                OrderedFloat(components[7].parse::<f64>().ok().unwrap_or_else(|| 0.0))
+                components[7].parse::<Money>().unwrap_or_default()
             } else {
-                OrderedFloat(components[6].parse::<f64>().ok().unwrap_or_else(|| 0.0))
+                components[6].parse::<Money>().unwrap_or_default()
             },
             transaction_type: if components[6].to_string().is_empty() {
                 TransactionType::Withdrawal
             } else {
                 TransactionType::Deposit
             },
-            is_reconciled: components[2].to_string() == "Y"
+            is_reconciled: components[2] == "Y",
+            splits: vec![]
+        }
+    }
+
+    /**
+     * spread the transaction across categories, failing if the splits do not add up to the amount.
+     * An empty list of splits puts the whole amount back in the transaction's category.
+     */
+    pub fn set_splits(&mut self, splits: Vec<Split>) -> Result<(), Error> {
+        check_splits(self.amount, &splits)?;
+
+        self.splits = splits;
+
+        Ok(())
+    }
+
+    /// make sure the splits add up to the amount, which is worth doing after the splits or amount are changed directly.
+    pub fn validate_splits(&self) -> Result<(), Error> {
+        check_splits(self.amount, &self.splits)
+    }
+
+    /// the amount of the transaction in each category, using the splits if there are any.
+    pub fn category_amounts(&self) -> Vec<(Option<&str>, Money)> {
+        if self.splits.is_empty() {
+            vec![(self.category.as_deref(), self.amount)]
+        } else {
+            self.splits.iter().map(|split| (split.category.as_deref(), split.amount)).collect()
         }
     }
+}
+
+// make sure splits add up to the given amount.
+fn check_splits(amount: Money, splits: &[Split]) -> Result<(), Error> {
+    if splits.is_empty() {
+        return Ok(())
+    }
+
+    let total = splits.iter().try_fold(Money::ZERO, |total, split| total.checked_add(split.amount));
+
+    match total {
+        Some(total) if total == amount => Ok(()),
+        _ => Err(Error::Splits { amount, total })
+    }
+}
 
+// implement trait needed to display item as string
+impl fmt::Display for Transaction {
     /// presents a string version of the transaction.
-    pub fn to_string(&self) -> String {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut transaction_string = String::new();
 
         let date_string = format!("{}\t", self.date.format("%Y-%m-%d"));
@@ -150,7 +199,7 @@ impl Transaction {
             let check_number_string = format!("{}\t", check_number);
             transaction_string.push_str(&check_number_string);
         } else {
-            transaction_string.push_str("\t");
+            transaction_string.push('\t');
         }
 
         transaction_string.push_str(if self.is_reconciled {
@@ -162,7 +211,7 @@ impl Transaction {
 
             transaction_string.push_str(&category_string);
         } else {
-            transaction_string.push_str("\t");
+            transaction_string.push('\t');
         }
 
         let vendor_string = format!("{}\t", self.vendor);
@@ -174,14 +223,14 @@ impl Transaction {
         transaction_string.push_str(&memo_string);
 
         let amount_string = if let TransactionType::Withdrawal = self.transaction_type {
-            format!("\t{:.2}", self.amount)
+            format!("\t{}", self.amount)
         } else {
-            format!("{:.2}\t", self.amount)
+            format!("{}\t", self.amount)
         };
 
         transaction_string.push_str(&amount_string);
 
-        return transaction_string;
+        write!(f, "{}", transaction_string)
     }
 }
 
@@ -195,14 +244,33 @@ impl PartialEq for Transaction {
         self.memo == other.memo &&
         self.amount == other.amount &&
         self.transaction_type == other.transaction_type &&
-        self.is_reconciled == other.is_reconciled
+        self.is_reconciled == other.is_reconciled &&
+        self.splits == other.splits
     }
 }
 
-// implement trait needed to display item as string
-impl fmt::Display for Transaction {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.to_string())
+// implement trait needed to parse a tab delimited row without a record id.
+impl FromStr for Transaction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split('\t').collect();
+
+        Ok(Layout::transaction().record_from_fields(&fields)?.transaction)
+    }
+}
+
+impl TryFrom<&str> for Transaction {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl Default for Transaction {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -213,13 +281,14 @@ impl fmt::Display for Transaction {
  */
 pub mod transaction_date_format {
     use super::*;
+    use crate::local_datetime_from_string::local_midnight;
 
     /// the format for the date that is expected.
-    pub const FORMAT: &'static str = "%Y-%m-%d";
+    pub const FORMAT: &str = "%Y-%m-%d";
 
     /// serialize dates as strings. This is typically not used directly, as serde grabs it automatically.
     pub fn serialize<S>(date: &DateTime<Local>, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer, {
-        let date_string = format!("{}", date.format(&FORMAT));
+        let date_string = format!("{}", date.format(FORMAT));
         serializer.serialize_str(&date_string)
     }
 
@@ -227,11 +296,8 @@ pub mod transaction_date_format {
     pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Local>, D::Error> where D: Deserializer<'de>, {
         let date_string = String::deserialize(deserializer)?;
         let naive_date = NaiveDate::parse_from_str(&date_string, FORMAT).map_err(serde::de::Error::custom)?;
-        let naive_datetime = naive_date.and_hms_opt(0, 0, 0).unwrap();
 
-        let local_datetime = Local.from_local_datetime(&naive_datetime).unwrap();
-
-        Ok(local_datetime)
+        Ok(local_midnight(naive_date))
     }
 
     /// verify a given string uses the appropriate date format.
@@ -243,10 +309,6 @@ pub mod transaction_date_format {
 }
 
 // create functions for use with setting default values.
-fn default_float() -> OrderedFloat<f64> {
-    OrderedFloat::<f64>(0.0)
-}
-
 fn default_reconciled() -> bool {
     false
 }
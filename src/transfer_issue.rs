@@ -0,0 +1,12 @@
+/// Represents a problem with a transfer between accounts, found by validating a workbook.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransferIssue {
+    /// only one half of the transfer was found, such as when the other half was deleted.
+    Orphaned { transfer_id: String, account_id: String, record_id: String },
+
+    /**
+     * the records sharing the transfer id, given as account id and record id, do not make up a single transfer.
+     * The halves should be a withdrawal and a deposit in different accounts, with the same date and amount.
+     */
+    Mismatched { transfer_id: String, records: Vec<(String, String)> }
+}
@@ -0,0 +1,246 @@
+// import custom types, so that several accounts can be kept together.
+use crate::{ account::Account, error::Error, local_datetime_from_string::local_midnight, money::Money, record::{ Record, default_id }, save_vec::write_file, transaction::Transaction, transaction_type::TransactionType, transfer_issue::TransferIssue };
+
+// import chrono, so that transfers can be dated.
+use chrono::NaiveDate;
+
+// import Serde, so that workbooks can be stored as JSON.
+use serde::{ Serialize, Deserialize };
+
+// import things needed for dealing with files.
+use std::{ collections::BTreeMap, fs::File, io::{ BufReader, Read, Write }, path::Path };
+
+/// the version of the workbook format that is written.
+const VERSION: u32 = 1;
+
+/**
+ * Holds several accounts, such as checking, savings, and a credit card, so they can be loaded and saved together.
+ * Workbooks are stored as a JSON object with a list of accounts, each holding its records the same way a bcheck file does,
+ * and a single account can still be saved as a bcheck file with Save.
+ * # Example
+ * ```
+ * use bcheck::{ Account, AccountType, Workbook };
+ *
+ * let mut workbook = Workbook::new();
+ *
+ * workbook.add_account(Account::new("Checking")).unwrap();
+ * workbook.add_account(Account::new("Savings").with_type(AccountType::Savings)).unwrap();
+ *
+ * assert!(workbook.add_account(Account::new("Savings")).is_err());
+ * assert_eq!(workbook.accounts().len(), 2);
+ * ```
+ */
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Workbook {
+    #[serde(default = "default_version")]
+    version: u32,
+    accounts: Vec<Account>
+}
+
+impl Workbook {
+    /// create an empty workbook.
+    pub fn new() -> Workbook {
+        Workbook {
+            version: VERSION,
+            accounts: vec![]
+        }
+    }
+
+    /// load a workbook from the given path.
+    pub fn from_file<P: AsRef<Path>>(f: P) -> Result<Workbook, Error> {
+        let file = File::open(&f).map_err(|error| Error::from(error).with_path(&f))?;
+
+        Workbook::from_reader(file).map_err(|error| error.with_path(f))
+    }
+
+    /// load a workbook from anything that can be read, failing if two accounts share an id or name, or if the splits of a transaction do not add up to its amount.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Workbook, Error> {
+        let stored_workbook: Workbook = serde_json::from_reader(BufReader::new(reader))?;
+        let mut workbook = Workbook::new();
+
+        for account in stored_workbook.accounts {
+            for record in account.register.iter() {
+                record.transaction.validate_splits()?;
+            }
+
+            workbook.add_account(account)?;
+        }
+
+        Ok(workbook)
+    }
+
+    /// write the workbook as JSON to anything that can be written to.
+    pub fn to_writer<W: Write>(&self, writer: W) -> Result<(), Error> {
+        serde_json::to_writer_pretty(writer, self)?;
+
+        Ok(())
+    }
+
+    /// save the workbook as JSON to the given path.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        write_file(&path, |output| self.to_writer(output))
+    }
+
+    /// the accounts, in the order they were added.
+    pub fn accounts(&self) -> &[Account] {
+        &self.accounts
+    }
+
+    /// the account with the given id.
+    pub fn account(&self, id: &str) -> Option<&Account> {
+        self.accounts.iter().find(|account| account.id == id)
+    }
+
+    /// the account with the given id, so that it can be changed.
+    pub fn account_mut(&mut self, id: &str) -> Option<&mut Account> {
+        self.accounts.iter_mut().find(|account| account.id == id)
+    }
+
+    /// the account with the given name, ignoring case.
+    pub fn account_named(&self, name: &str) -> Option<&Account> {
+        self.accounts.iter().find(|account| account.name.eq_ignore_ascii_case(name))
+    }
+
+    /// the account with the given name, ignoring case, so that it can be changed.
+    pub fn account_named_mut(&mut self, name: &str) -> Option<&mut Account> {
+        self.accounts.iter_mut().find(|account| account.name.eq_ignore_ascii_case(name))
+    }
+
+    /// add an account, failing if another account has the same id or name.
+    pub fn add_account(&mut self, account: Account) -> Result<(), Error> {
+        if self.account(&account.id).is_some() {
+            return Err(Error::DuplicateAccount(account.id))
+        }
+
+        if self.account_named(&account.name).is_some() {
+            return Err(Error::DuplicateAccount(account.name))
+        }
+
+        self.accounts.push(account);
+
+        Ok(())
+    }
+
+    /// take the account with the given id out of the workbook.
+    pub fn remove_account(&mut self, id: &str) -> Option<Account> {
+        let index = self.accounts.iter().position(|account| account.id == id)?;
+
+        Some(self.accounts.remove(index))
+    }
+
+    /**
+     * move money from one account to another, adding a withdrawal to the first and a deposit to the second.
+     * Both records share a transfer id, which is returned, so that they can be kept in sync.
+     */
+    pub fn transfer(&mut self, from_account_id: &str, to_account_id: &str, date: NaiveDate, amount: Money, memo: &str) -> Result<String, Error> {
+        if from_account_id == to_account_id {
+            return Err(Error::Invalid { value: to_account_id.to_string(), expected: "a different account to transfer to" })
+        }
+
+        if amount.is_negative() || amount.is_zero() {
+            return Err(Error::Invalid { value: amount.to_string(), expected: "an amount greater than zero" })
+        }
+
+        let from_name = self.account(from_account_id).ok_or_else(|| Error::UnknownAccount(from_account_id.to_string()))?.name.clone();
+        let to_name = self.account(to_account_id).ok_or_else(|| Error::UnknownAccount(to_account_id.to_string()))?.name.clone();
+
+        let transfer_id = default_id();
+
+        let half = |vendor: String, transaction_type: TransactionType| Record {
+            id: default_id(),
+            transaction: Transaction {
+                date: local_midnight(date),
+                vendor,
+                memo: memo.to_string(),
+                amount,
+                transaction_type,
+                ..Transaction::new()
+            },
+            transfer_id: Some(transfer_id.clone())
+        };
+
+        let withdrawal = half(format!("Transfer to {}", to_name), TransactionType::Withdrawal);
+        let deposit = half(format!("Transfer from {}", from_name), TransactionType::Deposit);
+
+        self.account_mut(from_account_id).expect("account was found above").register.insert(withdrawal)?;
+        self.account_mut(to_account_id).expect("account was found above").register.insert(deposit)?;
+
+        Ok(transfer_id)
+    }
+
+    /**
+     * replace a record in the given account, returning the old one.
+     * If the record is half of a transfer, the date and amount of the other half are changed to match.
+     * Nothing is changed if the splits of either record would not add up to the amount.
+     */
+    pub fn update_record(&mut self, account_id: &str, record: Record) -> Result<Record, Error> {
+        record.transaction.validate_splits()?;
+
+        // work out the other halves first, so that a half whose splits no longer add up stops the whole update.
+        let mut other_halves: Vec<(usize, Record)> = vec![];
+
+        if let Some(transfer_id) = &record.transfer_id {
+            for (index, account) in self.accounts.iter().enumerate() {
+                for other in account.register.iter().filter(|other| other.transfer_id.as_ref() == Some(transfer_id) && other.id != record.id) {
+                    let mut other_half = other.clone();
+                    other_half.transaction.date = record.transaction.date;
+                    other_half.transaction.amount = record.transaction.amount;
+                    other_half.transaction.validate_splits()?;
+
+                    other_halves.push((index, other_half));
+                }
+            }
+        }
+
+        let account = self.account_mut(account_id).ok_or_else(|| Error::UnknownAccount(account_id.to_string()))?;
+        let old_record = account.register.update(record)?;
+
+        for (index, other_half) in other_halves {
+            self.accounts[index].register.update(other_half)?;
+        }
+
+        Ok(old_record)
+    }
+
+    /// take both halves of a transfer out of the workbook, returning how many records were removed.
+    pub fn remove_transfer(&mut self, transfer_id: &str) -> usize {
+        let mut count = 0;
+
+        for account in &mut self.accounts {
+            let ids: Vec<String> = account.register.iter().filter(|record| record.transfer_id.as_deref() == Some(transfer_id)).map(|record| record.id.clone()).collect();
+
+            for id in ids {
+                account.register.remove(&id);
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// look for transfers that are missing a half, or whose halves no longer match.
+    pub fn validate_transfers(&self) -> Vec<TransferIssue> {
+        let mut transfers: BTreeMap<&str, Vec<(&Account, &Record)>> = BTreeMap::new();
+
+        for account in &self.accounts {
+            for record in account.register.iter() {
+                if let Some(transfer_id) = &record.transfer_id {
+                    transfers.entry(transfer_id).or_default().push((account, record));
+                }
+            }
+        }
+
+        transfers.into_iter().filter_map(|(transfer_id, halves)| match halves.as_slice() {
+            [(account, record)] => Some(TransferIssue::Orphaned { transfer_id: transfer_id.to_string(), account_id: account.id.clone(), record_id: record.id.clone() }),
+            [(first_account, first), (second_account, second)] if first_account.id != second_account.id &&
+                first.transaction.transaction_type != second.transaction.transaction_type &&
+                first.transaction.date == second.transaction.date &&
+                first.transaction.amount == second.transaction.amount => None,
+            _ => Some(TransferIssue::Mismatched { transfer_id: transfer_id.to_string(), records: halves.iter().map(|(account, record)| (account.id.clone(), record.id.clone())).collect() })
+        }).collect()
+    }
+}
+
+fn default_version() -> u32 {
+    VERSION
+}